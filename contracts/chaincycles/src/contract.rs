@@ -8,18 +8,27 @@ mod state;
 use std::str::FromStr;
 
 use linera_sdk::{
+    bcs,
     linera_base_types::{AccountOwner, ChainId, WithContractAbi},
     views::{RootView, View},
     Contract, ContractRuntime,
 };
 
 use chaincycles::{
-    ChainCyclesAbi, ChainCyclesError, ChainCyclesResponse, ErrorResponse, GameRoom, GameStatus,
-    GameType, InstantiationArgument, Message, MoveData, MoveResponse, Operation, Player,
-    PlayerProfile, Rewards, RoomCreatedResponse, RoomJoinedResponse, SuccessResponse,
+    chess960_index, ChainCyclesAbi, ChainCyclesError, ChainCyclesResponse, ChessBoard,
+    DisputeVerdict, ErrorResponse, GameRoom, GameStatus, GameType, InstantiationArgument, Message,
+    MoveData, MoveRecord, MoveResponse, Operation, Player, PlayerProfile, RewardPayout, Rewards,
+    RoomCreatedResponse, RoomDivergence, RoomJoinedResponse, SuccessResponse, TakebackRequest,
+    TimeControl,
 };
 use state::ChainCyclesState;
 
+/// Number of rejected messages kept for diagnostics
+const MAX_REJECTED_MESSAGES: usize = 20;
+
+/// Number of early moves buffered while waiting for a gap to fill
+const MAX_PENDING_MOVES: usize = 32;
+
 pub struct ChainCyclesContract {
     state: ChainCyclesState,
     runtime: ContractRuntime<Self>,
@@ -59,17 +68,66 @@ impl Contract for ChainCyclesContract {
             None => return ChainCyclesError::NotAuthenticated.into_response(),
         };
 
+        // Chains shared by several owners can restrict who may act at all
+        let allowed = self.state.allowed_owners.get();
+        if !allowed.is_empty() && !allowed.contains(&owner) {
+            return ChainCyclesError::OwnerNotAllowed.into_response();
+        }
+
+        // Only the owner who created or joined the room may act on it, until
+        // its game is over and any allowed owner may move on to a new room
+        let is_room_operation = !matches!(
+            operation,
+            Operation::Register { .. }
+                | Operation::UpdateProfile { .. }
+                | Operation::SetAllowedOwners { .. }
+                | Operation::SyncInbox
+        );
+        let room_over = self
+            .state
+            .game_room
+            .get()
+            .as_ref()
+            .is_some_and(|r| r.is_over());
+        let moves_on = room_over
+            && matches!(
+                operation,
+                Operation::ClearRoom | Operation::CreateRoom { .. } | Operation::JoinRoom { .. }
+            );
+        let room_owner = self.state.room_owner.get();
+        if is_room_operation && !moves_on && room_owner.as_ref().is_some_and(|o| *o != owner) {
+            return ChainCyclesError::NotRoomOwner.into_response();
+        }
+
         match operation {
             // === Player Management ===
             Operation::Register { username } => self.handle_register(owner, username).await,
 
+            Operation::SetAllowedOwners { owners } => {
+                self.handle_set_allowed_owners(owner, owners).await
+            }
+
             Operation::UpdateProfile { username } => {
                 self.handle_update_profile(owner, username).await
             }
 
             // === Room Management ===
-            Operation::CreateRoom { game_type } => {
-                self.handle_create_room(owner, game_type).await
+            Operation::CreateRoom {
+                game_type,
+                starting_fen,
+                chess960,
+                rated,
+                time_control,
+            } => {
+                self.handle_create_room(
+                    owner,
+                    game_type,
+                    starting_fen,
+                    chess960,
+                    rated,
+                    time_control,
+                )
+                .await
             }
 
             Operation::JoinRoom { host_chain_id } => {
@@ -83,10 +141,34 @@ impl Contract for ChainCyclesContract {
             // === Gameplay ===
             Operation::MakeMove { move_data } => self.handle_make_move(owner, move_data).await,
 
+            Operation::ClaimDraw => self.handle_claim_draw(owner).await,
+
+            Operation::Resign => self.handle_resign(owner).await,
+
+            Operation::ClaimTimeout => self.handle_claim_timeout(owner).await,
+
+            Operation::TakeVacation { days } => self.handle_take_vacation(owner, days).await,
+
+            Operation::OfferDraw => self.handle_offer_draw(owner).await,
+
+            Operation::AcceptDraw => self.handle_accept_draw(owner).await,
+
+            Operation::DeclineDraw => self.handle_decline_draw(owner).await,
+
+            Operation::RequestTakeback { plies } => {
+                self.handle_request_takeback(owner, plies).await
+            }
+
+            Operation::AcceptTakeback => self.handle_accept_takeback(owner).await,
+
+            Operation::OpenDispute => self.handle_open_dispute(owner).await,
+
             // === Sync ===
             Operation::SyncInbox => ChainCyclesResponse::Success(SuccessResponse {
                 message: "Inbox synced".to_string(),
             }),
+
+            Operation::RequestResync => self.handle_request_resync(owner).await,
         }
     }
 
@@ -103,44 +185,92 @@ impl Contract for ChainCyclesContract {
 
             Message::GameStateSync { room } => {
                 // Joiner receives initial game state from host
-                self.state.game_room.set(Some(room));
+                self.handle_state_sync(room).await;
             }
 
-            Message::GameMoveSync { room } => {
-                // Receive move sync from opponent - update local state
-                self.state.game_room.set(Some(room));
+            Message::GameMoveSync { room_id, record } => {
+                // Replay the opponent's move through the same rules
+                self.handle_move_sync(room_id, record).await;
             }
 
-            Message::MatchEnded {
-                winner: _,
-                reason: _,
-                final_room,
-            } => {
-                self.state.game_room.set(Some(final_room));
+            Message::MatchEnded { winner, reason } => {
+                self.handle_match_ended(winner, reason).await;
             }
 
             Message::PlayerLeft {
-                player_chain_id: _,
-                player_wallet: _,
+                player_chain_id,
+                player_wallet,
             } => {
                 // Handle opponent leaving
-                if let Some(mut room) = self.state.game_room.get().clone() {
-                    if room.status == GameStatus::InProgress {
-                        room.status = GameStatus::Abandoned;
-                        self.state.game_room.set(Some(room));
-                    }
-                }
+                self.handle_player_left(player_chain_id, player_wallet)
+                    .await;
             }
 
             Message::RewardSync {
+                room_id,
                 player_wallet,
                 xp_earned,
                 coins_earned,
-                is_winner: _,
+                is_winner,
+            } => {
+                self.handle_reward_sync(room_id, player_wallet, xp_earned, coins_earned, is_winner)
+                    .await;
+            }
+
+            Message::DrawOffered { room_id } => {
+                self.handle_draw_offered(room_id).await;
+            }
+
+            Message::DrawAccepted { room_id } => {
+                self.handle_draw_accepted(room_id).await;
+            }
+
+            Message::DrawDeclined { room_id } => {
+                self.handle_draw_declined(room_id).await;
+            }
+
+            Message::TakebackRequested {
+                room_id,
+                plies,
+                sequence,
+            } => {
+                self.handle_takeback_requested(room_id, plies, sequence)
+                    .await;
+            }
+
+            Message::TakebackAccepted {
+                room_id,
+                plies,
+                sequence,
+                timestamp,
             } => {
-                self.apply_rewards(&player_wallet, xp_earned, coins_earned)
+                self.handle_takeback_accepted(room_id, plies, sequence, timestamp)
                     .await;
             }
+
+            Message::VacationTaken { room_id, days } => {
+                self.handle_vacation_taken(room_id, days).await;
+            }
+
+            Message::DisputeOpened { room_id } => {
+                self.handle_dispute_opened(room_id).await;
+            }
+
+            Message::DisputeSubmission { room } => {
+                self.handle_dispute_submission(room).await;
+            }
+
+            Message::DisputeRuling { verdict } => {
+                self.handle_dispute_ruling(verdict).await;
+            }
+
+            Message::ResyncRequest { room_id, since } => {
+                self.handle_resync_request(room_id, since).await;
+            }
+
+            Message::ResyncReply { room_id, records } => {
+                self.handle_resync_reply(room_id, records).await;
+            }
         }
     }
 
@@ -185,6 +315,8 @@ impl ChainCyclesContract {
             xp: 0,
             coins: 100, // Starting coins
             created_at: now,
+            vacation_days: PlayerProfile::VACATION_DAYS,
+            vacation_refilled_at: now,
         };
 
         self.state.players.insert(&wallet_key, profile).unwrap();
@@ -225,6 +357,10 @@ impl ChainCyclesContract {
         &mut self,
         owner: AccountOwner,
         game_type: GameType,
+        starting_fen: Option<String>,
+        chess960: Option<bool>,
+        rated: Option<bool>,
+        time_control: Option<TimeControl>,
     ) -> ChainCyclesResponse {
         // Check if already hosting
         if *self.state.is_hosting.get() {
@@ -240,10 +376,62 @@ impl ChainCyclesContract {
         let now = self.runtime.system_time();
         let chain_id = self.runtime.chain_id();
 
-        let room = GameRoom::new(chain_id, owner, profile.username.clone(), game_type, now);
+        let mut room = GameRoom::new(chain_id, owner, profile.username.clone(), game_type, now);
+        room.hub_chain_id = self.hub_chain_id();
+        room.rated = rated.unwrap_or(true);
+
+        if let Some(control) = time_control {
+            if control.base_secs == 0
+                || control.base_secs > TimeControl::MAX_BASE_SECS
+                || control.bonus_secs > TimeControl::MAX_BONUS_SECS
+            {
+                return ChainCyclesError::InvalidTimeControl.into_response();
+            }
+            room.time_control = Some(control);
+            room.clocks = room.starting_clocks();
+        }
+
+        // Custom starting positions and Chess960 are only supported for chess
+        let chess960 = chess960.unwrap_or(false);
+        if starting_fen.is_some() || chess960 {
+            if game_type != GameType::Chess {
+                return ChainCyclesError::InvalidFen(
+                    "only chess rooms accept chess options".to_string(),
+                )
+                .into_response();
+            }
+            let mut board = match starting_fen {
+                Some(fen) => match ChessBoard::from_fen(&fen) {
+                    Ok(b) => b,
+                    Err(e) => return ChainCyclesError::InvalidFen(e).into_response(),
+                },
+                // Back-rank shuffle seeded from the host chain and creation time
+                None => {
+                    let seed = format!("{}:{}", chain_id, now.micros());
+                    ChessBoard::new_chess960(chess960_index(&seed))
+                }
+            };
+            board.chess960 |= chess960;
+            if board.outcome().is_some() {
+                return ChainCyclesError::InvalidFen("position is already decided".to_string())
+                    .into_response();
+            }
+            // White is always Player One, so black to move means Player Two starts
+            room.current_turn = if board.white_turn {
+                Player::One
+            } else {
+                Player::Two
+            };
+            room.chess_board = Some(board);
+        }
 
+        if let Some(previous) = self.state.game_room.get().clone() {
+            self.keep_unpaid_room(&previous).await;
+        }
         self.state.game_room.set(Some(room.clone()));
+        self.state.pending_moves.set(Vec::new());
         self.state.is_hosting.set(true);
+        self.state.room_owner.set(Some(owner));
 
         ChainCyclesResponse::RoomCreated(RoomCreatedResponse {
             host_chain_id: chain_id.to_string(),
@@ -251,6 +439,37 @@ impl ChainCyclesContract {
         })
     }
 
+    /// Replace the chain's owner allowlist; the caller and the current room's
+    /// owner must stay on it
+    async fn handle_set_allowed_owners(
+        &mut self,
+        owner: AccountOwner,
+        owners: Vec<AccountOwner>,
+    ) -> ChainCyclesResponse {
+        if !owners.is_empty() && !owners.contains(&owner) {
+            return ChainCyclesError::OwnerNotAllowed.into_response();
+        }
+
+        // The first list is set by one of the chain's owners; after that only
+        // listed owners get this far
+        let first_list = self.state.allowed_owners.get().is_empty();
+        if first_list && !self.runtime.chain_ownership().verify_owner(&owner) {
+            return ChainCyclesError::OwnerNotAllowed.into_response();
+        }
+
+        // The owner playing the current room cannot be locked out of it
+        let room_owner = *self.state.room_owner.get();
+        if room_owner.is_some_and(|o| !owners.is_empty() && !owners.contains(&o)) {
+            return ChainCyclesError::NotRoomOwner.into_response();
+        }
+
+        self.state.allowed_owners.set(owners);
+
+        ChainCyclesResponse::Success(SuccessResponse {
+            message: "Allowed owners updated".to_string(),
+        })
+    }
+
     async fn handle_join_room(
         &mut self,
         owner: AccountOwner,
@@ -292,6 +511,7 @@ impl ChainCyclesContract {
 
         // Store that we're joining this room
         self.state.joined_host_chain.set(Some(host_chain_id.clone()));
+        self.state.room_owner.set(Some(owner));
 
         // Add to recent rooms
         let mut recent = self.state.recent_rooms.get().clone();
@@ -310,6 +530,9 @@ impl ChainCyclesContract {
     }
 
     /// Host receives join request
+    ///
+    /// The joiner's chain and wallet come from the message origin and the
+    /// authenticated signer; the claimed values in the body must match them.
     async fn handle_join_request(
         &mut self,
         joiner_chain_id: String,
@@ -331,6 +554,31 @@ impl ChainCyclesContract {
             return;
         }
 
+        let origin = self
+            .runtime
+            .message_origin_chain_id()
+            .map(|c| c.to_string());
+        let signer = self
+            .runtime
+            .authenticated_signer()
+            .map(|owner| format!("{:?}", owner));
+        let (joiner_chain_id, joiner_wallet) = match (origin, signer) {
+            (Some(chain), Some(wallet))
+                if chain == joiner_chain_id
+                    && wallet == joiner_wallet
+                    && !room.player_chain_ids.contains(&chain) =>
+            {
+                (chain, wallet)
+            }
+            (origin, signer) => {
+                self.record_rejection(format!(
+                    "join request from {:?} signed by {:?} claims chain {} and wallet {}",
+                    origin, signer, joiner_chain_id, joiner_wallet
+                ));
+                return;
+            }
+        };
+
         let now = self.runtime.system_time();
 
         // Add joiner
@@ -349,10 +597,61 @@ impl ChainCyclesContract {
         }
     }
 
+    /// Opponent left; only honoured from the other player's chain and wallet
+    async fn handle_player_left(&mut self, player_chain_id: String, player_wallet: String) {
+        let mut room = match self.state.game_room.get().clone() {
+            Some(r) => r,
+            None => return,
+        };
+
+        let my_chain = self.runtime.chain_id().to_string();
+        let origin = self
+            .runtime
+            .message_origin_chain_id()
+            .map(|c| c.to_string());
+        let signer = self
+            .runtime
+            .authenticated_signer()
+            .map(|owner| format!("{:?}", owner));
+        let sender_index = origin
+            .as_ref()
+            .filter(|o| **o != my_chain && **o == player_chain_id)
+            .and_then(|o| room.player_chain_ids.iter().position(|c| c == o));
+        let is_player = sender_index.is_some_and(|i| {
+            signer.as_ref() == Some(&player_wallet) && room.player_wallets.get(i) == signer.as_ref()
+        });
+        if !is_player {
+            self.record_rejection(format!(
+                "player left from {:?} signed by {:?} claims chain {}",
+                origin, signer, player_chain_id
+            ));
+            return;
+        }
+
+        if room.status == GameStatus::InProgress {
+            room.status = GameStatus::Abandoned;
+            self.state.game_room.set(Some(room));
+        }
+    }
+
     async fn handle_leave_room(&mut self, owner: AccountOwner) -> ChainCyclesResponse {
         let wallet_key = format!("{:?}", owner);
 
         if let Some(room) = self.state.game_room.get().clone() {
+            // Leaving a game in progress forfeits it, exactly like ClearRoom
+            if room.status == GameStatus::InProgress {
+                let player = match room.player_wallets.iter().position(|w| *w == wallet_key) {
+                    Some(0) => Player::One,
+                    Some(1) => Player::Two,
+                    _ => return ChainCyclesError::NotInRoom.into_response(),
+                };
+                self.forfeit(room, player, "abandoned").await;
+                return ChainCyclesResponse::Success(SuccessResponse {
+                    message: "Game forfeited; leave again to remove the room".to_string(),
+                });
+            }
+
+            self.keep_unpaid_room(&room).await;
             let my_chain = self.runtime.chain_id().to_string();
 
             if *self.state.is_hosting.get() {
@@ -386,6 +685,7 @@ impl ChainCyclesContract {
             }
 
             self.state.game_room.set(None);
+            self.state.room_owner.set(None);
         }
 
         ChainCyclesResponse::Success(SuccessResponse {
@@ -393,191 +693,1279 @@ impl ChainCyclesContract {
         })
     }
 
-    async fn handle_clear_room(&mut self, _owner: AccountOwner) -> ChainCyclesResponse {
+    /// Clear a finished room; clearing a game in progress forfeits it first
+    async fn handle_clear_room(&mut self, owner: AccountOwner) -> ChainCyclesResponse {
+        let wallet_key = format!("{:?}", owner);
+
+        if let Some(room) = self.state.game_room.get().clone() {
+            let player = match room.player_wallets.iter().position(|w| *w == wallet_key) {
+                Some(0) => Some(Player::One),
+                Some(1) => Some(Player::Two),
+                _ => None,
+            };
+
+            match player {
+                // Once the game is over, any owner allowed on the chain may clear it
+                None if !room.is_over() => return ChainCyclesError::NotInRoom.into_response(),
+                Some(player) if room.status == GameStatus::InProgress => {
+                    self.forfeit(room, player, "abandoned").await;
+                    return ChainCyclesResponse::Success(SuccessResponse {
+                        message: "Game forfeited; clear again to remove the room".to_string(),
+                    });
+                }
+                _ => {}
+            }
+
+            self.keep_unpaid_room(&room).await;
+        }
+
         self.state.game_room.set(None);
         self.state.is_hosting.set(false);
         self.state.joined_host_chain.set(None);
+        self.state.room_owner.set(None);
 
         ChainCyclesResponse::Success(SuccessResponse {
             message: "Room cleared".to_string(),
         })
     }
 
-    // ========================================================================
-    // GAMEPLAY - DIRECT MOVES WITH CROSS-CHAIN SYNC
-    // ========================================================================
+    /// Set aside a finished room whose reward has not been paid on this chain
+    /// yet, so a RewardSync still in the inbox can be checked once it is gone
+    async fn keep_unpaid_room(&mut self, room: &GameRoom) {
+        if !room.is_finished() {
+            return;
+        }
+        let my_chain = self.runtime.chain_id().to_string();
+        let wallet = match room.player_chain_ids.iter().position(|c| *c == my_chain) {
+            Some(i) => &room.player_wallets[i],
+            None => return,
+        };
 
-    async fn handle_make_move(
-        &mut self,
-        owner: AccountOwner,
-        move_data: MoveData,
-    ) -> ChainCyclesResponse {
+        let claim_key = format!("{}/{}", room.room_id(), wallet);
+        if let Ok(None) = self.state.claimed_rewards.get(&claim_key).await {
+            let _ = self.state.unpaid_rooms.insert(&room.room_id(), room.clone());
+        }
+    }
+
+    /// Resign the game in progress
+    async fn handle_resign(&mut self, owner: AccountOwner) -> ChainCyclesResponse {
         let wallet_key = format!("{:?}", owner);
 
-        // Get current room state
-        let mut room = match self.state.game_room.get().clone() {
+        let room = match self.state.game_room.get().clone() {
             Some(r) => r,
             None => return ChainCyclesError::RoomNotFound.into_response(),
         };
 
-        // Verify game is in progress
         if room.status != GameStatus::InProgress {
             return ChainCyclesError::GameNotInProgress.into_response();
         }
 
-        // Determine which player is making the move
-        let player_index = room
-            .player_wallets
-            .iter()
-            .position(|w| *w == wallet_key);
-
-        let player = match player_index {
+        let player = match room.player_wallets.iter().position(|w| *w == wallet_key) {
             Some(0) => Player::One,
             Some(1) => Player::Two,
             _ => return ChainCyclesError::NotInRoom.into_response(),
         };
 
-        // Verify it's this player's turn (except for Battleship setup)
-        let is_battleship_setup = room.game_type == GameType::Battleship
-            && room.battleship_board.as_ref().map(|b| b.setup_phase).unwrap_or(false);
+        self.forfeit(room, player, "resigned").await;
 
-        if !is_battleship_setup && room.current_turn != player {
-            return ChainCyclesError::NotYourTurn.into_response();
-        }
+        ChainCyclesResponse::Move(MoveResponse {
+            success: true,
+            game_ended: true,
+            winner: Some(player.other()),
+            message: "Resigned".to_string(),
+        })
+    }
 
-        // Process move based on game type
-        let (game_ended, winner, switch_turn) = match room.game_type {
-            GameType::Chess => self.process_chess_move(&mut room, player, &move_data),
-            GameType::ConnectFour => self.process_connect_four_move(&mut room, player, &move_data),
-            GameType::Reversi => self.process_reversi_move(&mut room, player, &move_data),
-            GameType::Gomoku => self.process_gomoku_move(&mut room, player, &move_data),
-            GameType::Battleship => self.process_battleship_move(&mut room, player, &move_data),
-            GameType::Mancala => self.process_mancala_move(&mut room, player, &move_data),
+    /// Win on time: the opponent is to move and their clock has run out
+    async fn handle_claim_timeout(&mut self, owner: AccountOwner) -> ChainCyclesResponse {
+        let (room, player) = match self.active_room_player(owner) {
+            Ok(r) => r,
+            Err(e) => return e.into_response(),
         };
 
-        let (game_ended, winner, switch_turn) = match (game_ended, winner, switch_turn) {
-            (Ok(ended), Ok(w), Ok(switch)) => (ended, w, switch),
-            _ => return ChainCyclesError::InvalidMove.into_response(),
+        let now = self.runtime.system_time().micros();
+        let loser = match room.timeout_loser(player, now) {
+            Some(loser) => loser,
+            None => return ChainCyclesError::TimeNotExpired.into_response(),
         };
 
-        // Update turn if needed
-        if switch_turn && !game_ended {
-            room.current_turn = room.current_turn.other();
+        self.forfeit(room, loser, "timeout").await;
+
+        ChainCyclesResponse::Move(MoveResponse {
+            success: true,
+            game_ended: true,
+            winner: Some(player),
+            message: "Won on time".to_string(),
+        })
+    }
+
+    /// Spend banked vacation days on the running correspondence clock
+    async fn handle_take_vacation(
+        &mut self,
+        owner: AccountOwner,
+        days: u64,
+    ) -> ChainCyclesResponse {
+        let (mut room, player) = match self.active_room_player(owner) {
+            Ok(r) => r,
+            Err(e) => return e.into_response(),
+        };
+
+        if !room
+            .time_control
+            .as_ref()
+            .is_some_and(|t| t.is_correspondence())
+        {
+            return ChainCyclesError::NotCorrespondence.into_response();
+        }
+        if room.current_turn != player {
+            return ChainCyclesError::NotYourTurn.into_response();
+        }
+        let now = self.runtime.system_time().micros();
+        if room.time_left(player, now) == Some(0) {
+            return ChainCyclesError::TimeExpired.into_response();
         }
 
-        // Update game status if ended
-        if game_ended {
-            if winner.is_some() {
-                room.status = GameStatus::Finished;
-                room.winner = winner;
-            } else {
-                room.status = GameStatus::Draw;
-            }
+        let wallet_key = format!("{:?}", owner);
+        let mut profile = match self.state.players.get(&wallet_key).await.ok().flatten() {
+            Some(p) => p,
+            None => return ChainCyclesError::NotRegistered.into_response(),
+        };
+        profile.refill_vacation(now);
+        let allowed = PlayerProfile::VACATION_DAYS.saturating_sub(room.vacation_taken(player));
+        if days == 0 || days > profile.vacation_days || days > allowed {
+            return ChainCyclesError::NoVacationLeft.into_response();
         }
 
-        // Update timestamp
-        room.last_move_at = self.runtime.system_time().micros();
+        profile.vacation_days -= days;
+        let _ = self.state.players.insert(&wallet_key, profile);
 
-        // Save updated room
+        room.take_vacation(player, days);
         self.state.game_room.set(Some(room.clone()));
 
-        // Send move sync to opponent's chain
-        let opponent_chain_str = if player == Player::One {
-            room.player_chain_ids.get(1)
-        } else {
-            room.player_chain_ids.get(0)
+        let vacation_msg = Message::VacationTaken {
+            room_id: room.room_id(),
+            days,
         };
+        self.send_to_opponent(&room, player, vacation_msg);
 
-        if let Some(chain_str) = opponent_chain_str {
-            if let Ok(opponent_chain) = ChainId::from_str(chain_str) {
-                let sync_msg = Message::GameMoveSync { room: room.clone() };
-                self.runtime
-                    .prepare_message(sync_msg)
-                    .with_authentication()
-                    .send_to(opponent_chain);
-            }
-        }
+        ChainCyclesResponse::Success(SuccessResponse {
+            message: format!("Took {} vacation days", days),
+        })
+    }
 
-        // Distribute rewards if game ended
-        if game_ended {
-            self.distribute_rewards(&room).await;
+    /// Opponent extended their running correspondence clock
+    async fn handle_vacation_taken(&mut self, room_id: String, days: u64) {
+        let (mut room, me) = match self.opponent_room(&room_id) {
+            Some(r) => r,
+            None => return,
+        };
+
+        let opponent = me.other();
+        let correspondence = room
+            .time_control
+            .as_ref()
+            .is_some_and(|t| t.is_correspondence());
+        let allowed = PlayerProfile::VACATION_DAYS.saturating_sub(room.vacation_taken(opponent));
+        if !correspondence || room.current_turn != opponent || days > allowed {
+            self.record_rejection(format!(
+                "vacation of {} days in {} not accepted",
+                days, room_id
+            ));
+            return;
         }
 
-        ChainCyclesResponse::Move(MoveResponse {
-            success: true,
-            game_ended,
-            winner,
-            message: if game_ended {
-                "Game ended".to_string()
-            } else {
-                "Move accepted".to_string()
-            },
-        })
+        room.take_vacation(opponent, days);
+        self.state.game_room.set(Some(room));
     }
 
     // ========================================================================
-    // GAME-SPECIFIC MOVE PROCESSING
+    // DRAW OFFERS
     // ========================================================================
 
-    fn process_chess_move(
+    /// Room and local player for an operation on a game in progress
+    fn active_room_player(
         &self,
-        room: &mut GameRoom,
-        player: Player,
-        move_data: &MoveData,
-    ) -> (Result<bool, ()>, Result<Option<Player>, ()>, Result<bool, ()>) {
-        let uci_move = match &move_data.secondary {
-            Some(m) => m,
-            None => return (Err(()), Err(()), Err(())),
-        };
+        owner: AccountOwner,
+    ) -> Result<(GameRoom, Player), ChainCyclesError> {
+        let wallet_key = format!("{:?}", owner);
 
-        let board = match &mut room.chess_board {
-            Some(b) => b,
-            None => return (Err(()), Err(()), Err(())),
+        let room = match self.state.game_room.get().clone() {
+            Some(r) => r,
+            None => return Err(ChainCyclesError::RoomNotFound),
         };
 
-        let is_white = player == Player::One;
-        if !board.make_move(uci_move, is_white) {
-            return (Err(()), Err(()), Err(()));
+        if room.status != GameStatus::InProgress {
+            return Err(ChainCyclesError::GameNotInProgress);
         }
 
-        // Chess doesn't have automatic win detection - rely on resignation/timeout
-        // For now, game continues until manual end
-        (Ok(false), Ok(None), Ok(true))
+        match room.player_wallets.iter().position(|w| *w == wallet_key) {
+            Some(0) => Ok((room, Player::One)),
+            Some(1) => Ok((room, Player::Two)),
+            _ => Err(ChainCyclesError::NotInRoom),
+        }
     }
 
-    fn process_connect_four_move(
-        &self,
-        room: &mut GameRoom,
-        player: Player,
-        move_data: &MoveData,
-    ) -> (Result<bool, ()>, Result<Option<Player>, ()>, Result<bool, ()>) {
-        let col = move_data.primary as u8;
-
-        let board = match &mut room.connect_four_board {
-            Some(b) => b,
-            None => return (Err(()), Err(()), Err(())),
+    /// Offer a draw; only while the opponent is to move, so they can
+    /// accept it before we move again
+    async fn handle_offer_draw(&mut self, owner: AccountOwner) -> ChainCyclesResponse {
+        let (mut room, player) = match self.active_room_player(owner) {
+            Ok(r) => r,
+            Err(e) => return e.into_response(),
         };
 
-        let row = board.drop_piece(col, player);
-        if row < 0 {
-            return (Err(()), Err(()), Err(())); // Invalid move
+        if room.current_turn == player {
+            return ChainCyclesError::NotYourTurn.into_response();
         }
-
-        // Check for winner
-        if let Some(winner) = board.check_winner() {
-            return (Ok(true), Ok(Some(winner)), Ok(false));
+        if room.draw_offer.is_some() {
+            return ChainCyclesError::DrawAlreadyOffered.into_response();
         }
 
-        // Check for draw
-        if board.is_full() {
-            return (Ok(true), Ok(None), Ok(false));
-        }
+        room.draw_offer = Some(player);
+        self.state.game_room.set(Some(room.clone()));
 
-        (Ok(false), Ok(None), Ok(true))
+        let offer_msg = Message::DrawOffered {
+            room_id: room.room_id(),
+        };
+        self.send_to_opponent(&room, player, offer_msg);
+
+        ChainCyclesResponse::Success(SuccessResponse {
+            message: "Draw offered".to_string(),
+        })
     }
 
-    fn process_reversi_move(
-        &self,
+    /// Accept the opponent's draw offer; only on our turn, while the
+    /// offerer cannot move and withdraw it
+    async fn handle_accept_draw(&mut self, owner: AccountOwner) -> ChainCyclesResponse {
+        let (mut room, player) = match self.active_room_player(owner) {
+            Ok(r) => r,
+            Err(e) => return e.into_response(),
+        };
+
+        if room.draw_offer != Some(player.other()) {
+            return ChainCyclesError::NoDrawOffer.into_response();
+        }
+        if room.current_turn != player {
+            return ChainCyclesError::NotYourTurn.into_response();
+        }
+
+        room.status = GameStatus::Draw;
+        room.winner = None;
+        room.end_reason = Some("draw_agreed".to_string());
+        room.draw_offer = None;
+        room.last_move_at = self.runtime.system_time().micros();
+        self.state.game_room.set(Some(room.clone()));
+
+        let accept_msg = Message::DrawAccepted {
+            room_id: room.room_id(),
+        };
+        self.send_to_opponent(&room, player, accept_msg);
+
+        self.distribute_rewards(&room).await;
+
+        ChainCyclesResponse::Move(MoveResponse {
+            success: true,
+            game_ended: true,
+            winner: None,
+            message: "Draw agreed".to_string(),
+        })
+    }
+
+    /// Decline the opponent's draw offer
+    async fn handle_decline_draw(&mut self, owner: AccountOwner) -> ChainCyclesResponse {
+        let (mut room, player) = match self.active_room_player(owner) {
+            Ok(r) => r,
+            Err(e) => return e.into_response(),
+        };
+
+        if room.draw_offer != Some(player.other()) {
+            return ChainCyclesError::NoDrawOffer.into_response();
+        }
+
+        room.draw_offer = None;
+        self.state.game_room.set(Some(room.clone()));
+
+        let decline_msg = Message::DrawDeclined {
+            room_id: room.room_id(),
+        };
+        self.send_to_opponent(&room, player, decline_msg);
+
+        ChainCyclesResponse::Success(SuccessResponse {
+            message: "Draw declined".to_string(),
+        })
+    }
+
+    /// Room and local player for a message from the opponent's chain about a
+    /// game in progress
+    fn opponent_room(&mut self, room_id: &str) -> Option<(GameRoom, Player)> {
+        let my_chain = self.runtime.chain_id().to_string();
+        let origin = self
+            .runtime
+            .message_origin_chain_id()
+            .map(|c| c.to_string());
+
+        let room = match self.state.game_room.get().clone() {
+            Some(r) if r.room_id() == room_id && r.status == GameStatus::InProgress => r,
+            _ => {
+                self.record_rejection(format!("message for inactive room {}", room_id));
+                return None;
+            }
+        };
+
+        let me = match room.player_chain_ids.iter().position(|c| *c == my_chain) {
+            Some(0) => Player::One,
+            Some(1) => Player::Two,
+            _ => return None,
+        };
+        if origin.as_ref() != room.player_chain_ids.get(me.other().index()) {
+            self.record_rejection(format!("message from unexpected chain {:?}", origin));
+            return None;
+        }
+
+        Some((room, me))
+    }
+
+    /// Opponent offered a draw
+    async fn handle_draw_offered(&mut self, room_id: String) {
+        if let Some((mut room, me)) = self.opponent_room(&room_id) {
+            room.draw_offer = Some(me.other());
+            self.state.game_room.set(Some(room));
+        }
+    }
+
+    /// Opponent accepted our draw offer on their turn
+    async fn handle_draw_accepted(&mut self, room_id: String) {
+        let (mut room, me) = match self.opponent_room(&room_id) {
+            Some(r) => r,
+            None => return,
+        };
+
+        if room.draw_offer != Some(me) || room.current_turn != me.other() {
+            self.record_rejection(format!(
+                "draw acceptance without a live offer in {}",
+                room_id
+            ));
+            return;
+        }
+
+        room.status = GameStatus::Draw;
+        room.winner = None;
+        room.end_reason = Some("draw_agreed".to_string());
+        room.draw_offer = None;
+        room.last_move_at = self.runtime.system_time().micros();
+        self.state.game_room.set(Some(room));
+    }
+
+    /// Opponent declined our draw offer
+    async fn handle_draw_declined(&mut self, room_id: String) {
+        if let Some((mut room, me)) = self.opponent_room(&room_id) {
+            if room.draw_offer == Some(me) {
+                room.draw_offer = None;
+                self.state.game_room.set(Some(room));
+            }
+        }
+    }
+
+    // ========================================================================
+    // TAKEBACKS
+    // ========================================================================
+
+    /// Ask the opponent to undo the last `plies` moves of an unrated game
+    async fn handle_request_takeback(
+        &mut self,
+        owner: AccountOwner,
+        plies: u64,
+    ) -> ChainCyclesResponse {
+        let (mut room, player) = match self.active_room_player(owner) {
+            Ok(r) => r,
+            Err(e) => return e.into_response(),
+        };
+
+        if room.rated {
+            return ChainCyclesError::RatedRoom.into_response();
+        }
+        if plies == 0 || plies > room.sequence {
+            return ChainCyclesError::InvalidTakeback.into_response();
+        }
+
+        room.takeback_request = Some(TakebackRequest {
+            player,
+            plies,
+            sequence: room.sequence,
+        });
+        self.state.game_room.set(Some(room.clone()));
+
+        let request_msg = Message::TakebackRequested {
+            room_id: room.room_id(),
+            plies,
+            sequence: room.sequence,
+        };
+        self.send_to_opponent(&room, player, request_msg);
+
+        ChainCyclesResponse::Success(SuccessResponse {
+            message: format!("Takeback of {} moves requested", plies),
+        })
+    }
+
+    /// Approve the opponent's takeback request; only on our turn, while the
+    /// requester cannot move and make it stale
+    async fn handle_accept_takeback(&mut self, owner: AccountOwner) -> ChainCyclesResponse {
+        let (room, player) = match self.active_room_player(owner) {
+            Ok(r) => r,
+            Err(e) => return e.into_response(),
+        };
+
+        let request = match room.takeback_request.clone() {
+            Some(t) if t.player == player.other() && t.sequence == room.sequence => t,
+            _ => return ChainCyclesError::NoTakebackRequest.into_response(),
+        };
+        if room.current_turn != player {
+            return ChainCyclesError::NotYourTurn.into_response();
+        }
+
+        let now = self.runtime.system_time().micros();
+        let rewound = match self.rewind(&room, request.plies, now) {
+            Ok(r) => r,
+            Err(e) => return e.into_response(),
+        };
+        self.state.game_room.set(Some(rewound.clone()));
+        self.state.pending_moves.set(Vec::new());
+
+        let accept_msg = Message::TakebackAccepted {
+            room_id: room.room_id(),
+            plies: request.plies,
+            sequence: request.sequence,
+            timestamp: now,
+        };
+        self.send_to_opponent(&room, player, accept_msg);
+
+        ChainCyclesResponse::Success(SuccessResponse {
+            message: format!("Took back {} moves", request.plies),
+        })
+    }
+
+    /// Opponent asked to undo moves; only valid for the position we are in
+    async fn handle_takeback_requested(&mut self, room_id: String, plies: u64, sequence: u64) {
+        let (mut room, me) = match self.opponent_room(&room_id) {
+            Some(r) => r,
+            None => return,
+        };
+
+        if room.rated || sequence != room.sequence || plies == 0 || plies > sequence {
+            self.record_rejection(format!(
+                "takeback request for move {} in {} not accepted",
+                sequence, room_id
+            ));
+            return;
+        }
+
+        room.takeback_request = Some(TakebackRequest {
+            player: me.other(),
+            plies,
+            sequence,
+        });
+        self.state.game_room.set(Some(room));
+    }
+
+    /// Opponent approved our takeback request on their turn
+    async fn handle_takeback_accepted(
+        &mut self,
+        room_id: String,
+        plies: u64,
+        sequence: u64,
+        timestamp: u64,
+    ) {
+        let (room, me) = match self.opponent_room(&room_id) {
+            Some(r) => r,
+            None => return,
+        };
+
+        let live = room
+            .takeback_request
+            .as_ref()
+            .is_some_and(|t| t.player == me && t.plies == plies && t.sequence == sequence);
+        if !live || room.sequence != sequence || room.current_turn != me.other() {
+            self.record_rejection(format!(
+                "takeback acceptance without a live request in {}",
+                room_id
+            ));
+            return;
+        }
+
+        match self.rewind(&room, plies, timestamp) {
+            Ok(rewound) => {
+                self.state.game_room.set(Some(rewound));
+                self.state.pending_moves.set(Vec::new());
+            }
+            Err(e) => self.record_rejection(format!("takeback in {} failed: {:?}", room_id, e)),
+        }
+    }
+
+    /// Rebuild `room` without its last `plies` moves by replaying the rest
+    /// of the log from the initial position, so Mancala extra turns,
+    /// Reversi passes and the hash chain come out as they were; vacation
+    /// days already taken stay taken and keep extending the clocks
+    fn rewind(&self, room: &GameRoom, plies: u64, now: u64) -> Result<GameRoom, ChainCyclesError> {
+        let keep = room
+            .move_log
+            .len()
+            .checked_sub(plies as usize)
+            .ok_or(ChainCyclesError::InvalidTakeback)?;
+
+        let mut rewound = room.initial_state();
+        for record in &room.move_log[..keep] {
+            self.apply_move(
+                &mut rewound,
+                record.player,
+                &record.move_data,
+                record.timestamp,
+            )?;
+        }
+        for player in [Player::One, Player::Two] {
+            let days = room.vacation_taken(player);
+            if days > 0 {
+                rewound.take_vacation(player, days);
+            }
+        }
+        rewound.last_move_at = now;
+        Ok(rewound)
+    }
+
+    /// End the game with `loser` forfeiting (conceding, or out of time),
+    /// tell the other chain and pay out
+    async fn forfeit(&mut self, mut room: GameRoom, loser: Player, reason: &str) {
+        let now = self.runtime.system_time().micros();
+        let my_chain = self.runtime.chain_id().to_string();
+        let other_chain = room.forfeit(loser, reason, now, &my_chain);
+        self.state.game_room.set(Some(room.clone()));
+
+        let end_msg = Message::MatchEnded {
+            winner: room.winner,
+            reason: reason.to_string(),
+        };
+        if let Some(chain) = other_chain {
+            self.runtime
+                .prepare_message(end_msg)
+                .with_authentication()
+                .send_to(chain);
+        }
+
+        self.distribute_rewards(&room).await;
+    }
+
+    // ========================================================================
+    // GAMEPLAY - DIRECT MOVES WITH CROSS-CHAIN SYNC
+    // ========================================================================
+
+    async fn handle_make_move(
+        &mut self,
+        owner: AccountOwner,
+        move_data: MoveData,
+    ) -> ChainCyclesResponse {
+        let wallet_key = format!("{:?}", owner);
+
+        // Get current room state
+        let mut room = match self.state.game_room.get().clone() {
+            Some(r) => r,
+            None => return ChainCyclesError::RoomNotFound.into_response(),
+        };
+
+        // Determine which player is making the move
+        let player_index = room
+            .player_wallets
+            .iter()
+            .position(|w| *w == wallet_key);
+
+        let player = match player_index {
+            Some(0) => Player::One,
+            Some(1) => Player::Two,
+            _ => return ChainCyclesError::NotInRoom.into_response(),
+        };
+
+        let now = self.runtime.system_time().micros();
+        let (game_ended, winner) = match self.apply_move(&mut room, player, &move_data, now) {
+            Ok(result) => result,
+            Err(e) => return e.into_response(),
+        };
+
+        // Save updated room
+        self.state.game_room.set(Some(room.clone()));
+
+        // Send the move to the opponent's chain, which replays it
+        if let Some(record) = room.move_log.last().cloned() {
+            let sync_msg = Message::GameMoveSync {
+                room_id: room.room_id(),
+                record,
+            };
+            self.send_to_opponent(&room, player, sync_msg);
+        }
+
+        // Distribute rewards if game ended
+        if game_ended {
+            self.distribute_rewards(&room).await;
+        }
+
+        ChainCyclesResponse::Move(MoveResponse {
+            success: true,
+            game_ended,
+            winner,
+            message: if game_ended {
+                "Game ended".to_string()
+            } else {
+                "Move accepted".to_string()
+            },
+        })
+    }
+
+    /// Validate and apply one move, appending it to the room's move log
+    ///
+    /// Local moves and moves replayed from the opponent's chain both go
+    /// through here, so every chain enforces exactly the same rules.
+    fn apply_move(
+        &self,
+        room: &mut GameRoom,
+        player: Player,
+        move_data: &MoveData,
+        timestamp: u64,
+    ) -> Result<(bool, Option<Player>), ChainCyclesError> {
+        // Verify game is in progress
+        if room.status != GameStatus::InProgress {
+            return Err(ChainCyclesError::GameNotInProgress);
+        }
+
+        // Verify it's this player's turn (Battleship setup is turn-based too,
+        // so both chains agree on a single move order)
+        if room.current_turn != player {
+            return Err(ChainCyclesError::NotYourTurn);
+        }
+
+        // Charge the mover's clock; a move made after the flag fell is refused
+        let clock = match room.time_left(player, timestamp) {
+            Some(0) => return Err(ChainCyclesError::TimeExpired),
+            left => left,
+        };
+
+        // Process move based on game type
+        let (game_ended, winner, switch_turn) = match room.game_type {
+            GameType::Chess => self.process_chess_move(room, player, move_data),
+            GameType::ConnectFour => self.process_connect_four_move(room, player, move_data),
+            GameType::Reversi => self.process_reversi_move(room, player, move_data),
+            GameType::Gomoku => self.process_gomoku_move(room, player, move_data),
+            GameType::Battleship => self.process_battleship_move(room, player, move_data),
+            GameType::Mancala => self.process_mancala_move(room, player, move_data),
+        };
+
+        let (game_ended, winner, switch_turn) = match (game_ended, winner, switch_turn) {
+            (Ok(ended), Ok(w), Ok(switch)) => (ended, w, switch),
+            _ => return Err(ChainCyclesError::InvalidMove),
+        };
+
+        // Update turn if needed
+        if switch_turn && !game_ended {
+            room.current_turn = room.current_turn.other();
+        }
+
+        // Update game status if ended (unless the game settled a special
+        // status itself, e.g. a Battleship forfeit)
+        if game_ended {
+            room.winner = winner;
+            if room.status == GameStatus::InProgress {
+                room.status = if winner.is_some() {
+                    GameStatus::Finished
+                } else {
+                    GameStatus::Draw
+                };
+            }
+        }
+
+        // Moving again withdraws the mover's own draw offer; any move
+        // makes a pending takeback request stale
+        if room.draw_offer == Some(player) {
+            room.draw_offer = None;
+        }
+        room.takeback_request = None;
+
+        if let (Some(left), Some(control)) = (clock, &room.time_control) {
+            room.clocks[player.index()] = control.after_move(left);
+        }
+
+        // Update timestamp, record the move and extend the hash chain
+        room.last_move_at = timestamp;
+        room.sequence += 1;
+        let mut record = MoveRecord {
+            sequence: room.sequence,
+            player,
+            move_data: move_data.clone(),
+            timestamp,
+            state_hash: String::new(),
+        };
+        record.state_hash = room.chain_hash(&record);
+        room.state_hash = record.state_hash.clone();
+        room.move_log.push(record);
+
+        Ok((game_ended, winner))
+    }
+
+    /// Replay a move received from the opponent's chain
+    ///
+    /// Moves apply strictly in sequence: duplicates of moves we already
+    /// have are ignored, and moves that arrive early wait in a small buffer
+    /// until the gap before them is filled.
+    async fn handle_move_sync(&mut self, room_id: String, record: MoveRecord) {
+        let mut room = match self.state.game_room.get().clone() {
+            Some(r) if r.room_id() == room_id => r,
+            _ => {
+                self.record_rejection(format!(
+                    "move {} for stale room {}",
+                    record.sequence, room_id
+                ));
+                return;
+            }
+        };
+
+        // The move must come from the chain of the player who made it
+        let my_chain = self.runtime.chain_id().to_string();
+        let origin = self
+            .runtime
+            .message_origin_chain_id()
+            .map(|c| c.to_string());
+        let expected = room.player_chain_ids.get(record.player.index());
+        if origin.is_none() || origin.as_ref() != expected || origin.as_ref() == Some(&my_chain) {
+            self.record_rejection(format!(
+                "move {} from unexpected chain {:?}",
+                record.sequence, origin
+            ));
+            return;
+        }
+
+        // Already applied: a redelivery is harmless, a different move is not
+        if record.sequence <= room.sequence {
+            if !Self::is_known_move(&room, &record) {
+                self.record_rejection(format!(
+                    "move {} conflicts with the move already applied",
+                    record.sequence
+                ));
+                let local_hash = Self::logged_hash(&room, record.sequence);
+                self.record_divergence(&room, &local_hash, &record);
+            }
+            return;
+        }
+
+        // Arrived early: hold it and ask the opponent for the missing moves
+        if record.sequence > room.sequence + 1 {
+            let mut pending = self.state.pending_moves.get().clone();
+            if !pending.iter().any(|p| p.sequence == record.sequence) {
+                if pending.len() >= MAX_PENDING_MOVES {
+                    self.record_rejection(format!(
+                        "move {} dropped, too many moves waiting",
+                        record.sequence
+                    ));
+                    return;
+                }
+                if pending.is_empty() {
+                    let request = Message::ResyncRequest {
+                        room_id: Some(room.room_id()),
+                        since: room.sequence,
+                    };
+                    self.send_to_opponent(&room, record.player.other(), request);
+                }
+                pending.push(record);
+                self.state.pending_moves.set(pending);
+            }
+            return;
+        }
+
+        if !self.replay_synced_move(&mut room, &record) {
+            return;
+        }
+
+        self.apply_pending_moves(&mut room);
+        self.state.game_room.set(Some(room));
+    }
+
+    /// Is `record` the move we already applied at its sequence number?
+    fn is_known_move(room: &GameRoom, record: &MoveRecord) -> bool {
+        record
+            .sequence
+            .checked_sub(1)
+            .and_then(|i| room.move_log.get(i as usize))
+            .is_some_and(|k| k.state_hash == record.state_hash)
+    }
+
+    /// State hash our own log recorded after move `sequence`
+    fn logged_hash(room: &GameRoom, sequence: u64) -> String {
+        sequence
+            .checked_sub(1)
+            .and_then(|i| room.move_log.get(i as usize))
+            .map(|k| k.state_hash.clone())
+            .unwrap_or_else(|| room.state_hash.clone())
+    }
+
+    /// Apply any buffered moves that are now next in line
+    fn apply_pending_moves(&mut self, room: &mut GameRoom) {
+        let mut pending = self.state.pending_moves.get().clone();
+        while let Some(i) = pending.iter().position(|p| p.sequence == room.sequence + 1) {
+            let next = pending.remove(i);
+            if !self.replay_synced_move(room, &next) {
+                break;
+            }
+        }
+        pending.retain(|p| p.sequence > room.sequence);
+        self.state.pending_moves.set(pending);
+    }
+
+    /// Ask for the moves this chain is missing
+    async fn handle_request_resync(&mut self, owner: AccountOwner) -> ChainCyclesResponse {
+        let wallet_key = format!("{:?}", owner);
+
+        match self.state.game_room.get().clone() {
+            Some(room) => {
+                let player = match room.player_wallets.iter().position(|w| *w == wallet_key) {
+                    Some(0) => Player::One,
+                    Some(1) => Player::Two,
+                    _ => return ChainCyclesError::NotInRoom.into_response(),
+                };
+                if room.player_chain_ids.len() < 2 {
+                    return ChainCyclesError::NoOpponent.into_response();
+                }
+                let request = Message::ResyncRequest {
+                    room_id: Some(room.room_id()),
+                    since: room.sequence,
+                };
+                self.send_to_opponent(&room, player, request);
+            }
+            None => {
+                // Joined, but the host's room never arrived
+                let host_chain = self
+                    .state
+                    .joined_host_chain
+                    .get()
+                    .as_ref()
+                    .and_then(|h| ChainId::from_str(h).ok());
+                let host_chain = match host_chain {
+                    Some(h) => h,
+                    None => return ChainCyclesError::RoomNotFound.into_response(),
+                };
+                let request = Message::ResyncRequest {
+                    room_id: None,
+                    since: 0,
+                };
+                self.runtime
+                    .prepare_message(request)
+                    .with_authentication()
+                    .send_to(host_chain);
+            }
+        }
+
+        ChainCyclesResponse::Success(SuccessResponse {
+            message: "Resync requested".to_string(),
+        })
+    }
+
+    /// Opponent asks for the moves after `since`
+    async fn handle_resync_request(&mut self, room_id: Option<String>, since: u64) {
+        let origin = self
+            .runtime
+            .message_origin_chain_id()
+            .map(|c| c.to_string());
+        let my_chain = self.runtime.chain_id().to_string();
+
+        let room = match self.state.game_room.get().clone() {
+            Some(r) if room_id.as_ref().is_none_or(|id| *id == r.room_id()) => r,
+            _ => {
+                self.record_rejection(format!("resync request for unknown room {:?}", room_id));
+                return;
+            }
+        };
+
+        let requester = match origin {
+            Some(o) if o != my_chain && room.player_chain_ids.contains(&o) => o,
+            _ => {
+                self.record_rejection(format!("resync request from unexpected chain {:?}", origin));
+                return;
+            }
+        };
+        let requester_chain = match ChainId::from_str(&requester) {
+            Ok(c) => c,
+            Err(_) => return,
+        };
+
+        // A joiner without a room first gets the room as it started
+        if room_id.is_none() {
+            let sync_msg = Message::GameStateSync {
+                room: room.initial_state(),
+            };
+            self.runtime
+                .prepare_message(sync_msg)
+                .with_authentication()
+                .send_to(requester_chain);
+        }
+
+        let reply = Message::ResyncReply {
+            room_id: room.room_id(),
+            records: room.move_log.iter().skip(since as usize).cloned().collect(),
+        };
+        self.runtime
+            .prepare_message(reply)
+            .with_authentication()
+            .send_to(requester_chain);
+    }
+
+    /// Replay the moves the opponent sent in answer to our resync request
+    ///
+    /// Each move is checked like a regular sync; the opponent may only fill
+    /// in our own moves when we are rebuilding the room from its start.
+    async fn handle_resync_reply(&mut self, room_id: String, records: Vec<MoveRecord>) {
+        let my_chain = self.runtime.chain_id().to_string();
+        let origin = self
+            .runtime
+            .message_origin_chain_id()
+            .map(|c| c.to_string());
+
+        let mut room = match self.state.game_room.get().clone() {
+            Some(r) if r.room_id() == room_id => r,
+            _ => {
+                self.record_rejection(format!("resync reply for stale room {}", room_id));
+                return;
+            }
+        };
+
+        let me = match room.player_chain_ids.iter().position(|c| *c == my_chain) {
+            Some(i) => i,
+            None => return,
+        };
+        let from_opponent = origin.as_ref() == room.player_chain_ids.get(1 - me);
+        if !from_opponent {
+            self.record_rejection(format!("resync reply from unexpected chain {:?}", origin));
+            return;
+        }
+
+        let rebuilding = room.sequence == 0;
+        for record in &records {
+            if record.sequence <= room.sequence {
+                if !Self::is_known_move(&room, record) {
+                    self.record_rejection(format!(
+                        "resync move {} conflicts with the move already applied",
+                        record.sequence
+                    ));
+                    let local_hash = Self::logged_hash(&room, record.sequence);
+                    self.record_divergence(&room, &local_hash, record);
+                    break;
+                }
+                continue;
+            }
+
+            if record.sequence != room.sequence + 1 {
+                break;
+            }
+            if record.player.index() == me && !rebuilding {
+                self.record_rejection(format!(
+                    "resync move {} is attributed to us but we never made it",
+                    record.sequence
+                ));
+                break;
+            }
+            if !self.replay_synced_move(&mut room, record) {
+                break;
+            }
+        }
+
+        self.apply_pending_moves(&mut room);
+        self.state.game_room.set(Some(room));
+    }
+
+    /// Apply the next move from the opponent's chain and check its state hash
+    ///
+    /// A move that is illegal here, or lands on a different state than the
+    /// sender's, is not applied; the room stays as it was and goes to the hub.
+    fn replay_synced_move(&mut self, room: &mut GameRoom, record: &MoveRecord) -> bool {
+        let mut replayed = room.clone();
+        match self.apply_move(
+            &mut replayed,
+            record.player,
+            &record.move_data,
+            record.timestamp,
+        ) {
+            Ok(_) if replayed.state_hash == record.state_hash => {
+                *room = replayed;
+                true
+            }
+            Ok(_) => {
+                self.record_divergence(room, &replayed.state_hash, record);
+                false
+            }
+            Err(e) => {
+                self.record_rejection(format!(
+                    "illegal move {} from {:?}: {:?}",
+                    record.sequence, record.player, e
+                ));
+                let local_hash = room.state_hash.clone();
+                self.record_divergence(room, &local_hash, record);
+                false
+            }
+        }
+    }
+
+    /// Remember the first move on which the two chains disagree and open a
+    /// dispute with our copy of the room, as it stood before that move
+    fn record_divergence(&mut self, room: &GameRoom, local_hash: &str, record: &MoveRecord) {
+        let already_diverged = self
+            .state
+            .room_divergence
+            .get()
+            .as_ref()
+            .is_some_and(|d| d.room_id == room.room_id());
+        if already_diverged {
+            return;
+        }
+
+        self.record_rejection(format!(
+            "state hash mismatch at move {}: local {} remote {}",
+            record.sequence, local_hash, record.state_hash
+        ));
+        self.state.room_divergence.set(Some(RoomDivergence {
+            room_id: room.room_id(),
+            sequence: record.sequence,
+            local_hash: local_hash.to_string(),
+            remote_hash: record.state_hash.clone(),
+            detected_at: self.runtime.system_time().micros(),
+        }));
+
+        self.submit_dispute(room);
+        let my_chain = self.runtime.chain_id().to_string();
+        let opponent = room
+            .other_chain(&my_chain)
+            .and_then(|c| ChainId::from_str(c).ok());
+        if let Some(opponent_chain) = opponent {
+            let opened_msg = Message::DisputeOpened {
+                room_id: room.room_id(),
+            };
+            self.runtime
+                .prepare_message(opened_msg)
+                .with_authentication()
+                .send_to(opponent_chain);
+        }
+    }
+
+    /// Joiner receives the initial room from the host it asked to join
+    async fn handle_state_sync(&mut self, room: GameRoom) {
+        let my_chain = self.runtime.chain_id().to_string();
+        let origin = self
+            .runtime
+            .message_origin_chain_id()
+            .map(|c| c.to_string());
+        let joined_host = self.state.joined_host_chain.get().clone();
+
+        let from_joined_host = origin.is_some()
+            && origin == joined_host
+            && origin.as_ref() == Some(&room.host_chain_id);
+        let is_fresh_room = room.status == GameStatus::InProgress
+            && room.sequence == 0
+            && room.player_chain_ids.get(1) == Some(&my_chain);
+
+        if !from_joined_host || !is_fresh_room {
+            self.record_rejection(format!("room state from unexpected chain {:?}", origin));
+            return;
+        }
+
+        if let Some(previous) = self.state.game_room.get().clone() {
+            self.keep_unpaid_room(&previous).await;
+        }
+        self.state.game_room.set(Some(room));
+        self.state.pending_moves.set(Vec::new());
+    }
+
+    /// Opponent ended the match without a move; only accepted if it is the
+    /// opponent conceding, or a draw claim our own copy of the board confirms
+    async fn handle_match_ended(&mut self, winner: Option<Player>, reason: String) {
+        let mut room = match self.state.game_room.get().clone() {
+            Some(r) => r,
+            None => return,
+        };
+
+        let my_chain = self.runtime.chain_id().to_string();
+        let origin = self
+            .runtime
+            .message_origin_chain_id()
+            .map(|c| c.to_string());
+        let opponent = match room.player_chain_ids.iter().position(|c| *c == my_chain) {
+            Some(0) => Player::Two,
+            Some(1) => Player::One,
+            _ => return,
+        };
+        if origin.as_ref() != room.player_chain_ids.get(opponent.index()) {
+            self.record_rejection(format!("match end from unexpected chain {:?}", origin));
+            return;
+        }
+
+        let conceded = winner == Some(opponent.other());
+        let now = self.runtime.system_time().micros();
+        let timed_out = winner == Some(opponent)
+            && reason == "timeout"
+            && room.timeout_loser(opponent, now).is_some();
+        let draw_confirmed = winner.is_none()
+            && room
+                .chess_board
+                .as_ref()
+                .and_then(|b| b.claimable_draw())
+                .is_some_and(|claim| claim.reason() == reason);
+        if room.status != GameStatus::InProgress || !(conceded || draw_confirmed || timed_out) {
+            self.record_rejection(format!("unconfirmed match end: {}", reason));
+            return;
+        }
+
+        room.status = if conceded || timed_out {
+            GameStatus::Forfeited
+        } else {
+            GameStatus::Draw
+        };
+        room.winner = winner;
+        room.end_reason = Some(reason);
+        room.last_move_at = now;
+        self.state.game_room.set(Some(room));
+    }
+
+    /// Keep a short log of rejected messages for diagnostics
+    fn record_rejection(&mut self, reason: String) {
+        let mut rejected = self.state.rejected_messages.get().clone();
+        rejected.insert(0, reason);
+        rejected.truncate(MAX_REJECTED_MESSAGES);
+        self.state.rejected_messages.set(rejected);
+    }
+
+    /// Claim a chess draw (fifty-move rule or threefold repetition)
+    async fn handle_claim_draw(&mut self, owner: AccountOwner) -> ChainCyclesResponse {
+        let wallet_key = format!("{:?}", owner);
+
+        let mut room = match self.state.game_room.get().clone() {
+            Some(r) => r,
+            None => return ChainCyclesError::RoomNotFound.into_response(),
+        };
+
+        if room.status != GameStatus::InProgress {
+            return ChainCyclesError::GameNotInProgress.into_response();
+        }
+
+        let player = match room.player_wallets.iter().position(|w| *w == wallet_key) {
+            Some(0) => Player::One,
+            Some(1) => Player::Two,
+            _ => return ChainCyclesError::NotInRoom.into_response(),
+        };
+
+        let claim = match room.chess_board.as_ref().and_then(|b| b.claimable_draw()) {
+            Some(c) => c,
+            None => return ChainCyclesError::DrawNotClaimable.into_response(),
+        };
+
+        room.status = GameStatus::Draw;
+        room.winner = None;
+        room.end_reason = Some(claim.reason().to_string());
+        room.last_move_at = self.runtime.system_time().micros();
+
+        self.state.game_room.set(Some(room.clone()));
+
+        let end_msg = Message::MatchEnded {
+            winner: None,
+            reason: claim.reason().to_string(),
+        };
+        self.send_to_opponent(&room, player, end_msg);
+
+        self.distribute_rewards(&room).await;
+
+        ChainCyclesResponse::Move(MoveResponse {
+            success: true,
+            game_ended: true,
+            winner: None,
+            message: "Draw claimed".to_string(),
+        })
+    }
+
+    /// Send a message to the chain of `player`'s opponent, if there is one
+    fn send_to_opponent(&mut self, room: &GameRoom, player: Player, message: Message) {
+        let opponent_chain_str = room.player_chain_ids.get(player.other().index());
+
+        if let Some(chain_str) = opponent_chain_str {
+            if let Ok(opponent_chain) = ChainId::from_str(chain_str) {
+                self.runtime
+                    .prepare_message(message)
+                    .with_authentication()
+                    .send_to(opponent_chain);
+            }
+        }
+    }
+
+    // ========================================================================
+    // GAME-SPECIFIC MOVE PROCESSING
+    // ========================================================================
+
+    fn process_chess_move(
+        &self,
+        room: &mut GameRoom,
+        player: Player,
+        move_data: &MoveData,
+    ) -> (Result<bool, ()>, Result<Option<Player>, ()>, Result<bool, ()>) {
+        let notation = match &move_data.secondary {
+            Some(m) => m,
+            None => return (Err(()), Err(()), Err(())),
+        };
+
+        let board = match &mut room.chess_board {
+            Some(b) => b,
+            None => return (Err(()), Err(()), Err(())),
+        };
+
+        // Accept either UCI or SAN input
+        let chess_move = match board.parse_move(notation) {
+            Some(m) => m,
+            None => return (Err(()), Err(()), Err(())),
+        };
+
+        let is_white = player == Player::One;
+        if !board.make_chess_move(&chess_move, is_white) {
+            return (Err(()), Err(()), Err(()));
+        }
+
+        // Check for checkmate or stalemate
+        if let Some(outcome) = board.outcome() {
+            room.end_reason = Some(outcome.reason().to_string());
+            return (Ok(true), Ok(outcome.winner()), Ok(false));
+        }
+
+        (Ok(false), Ok(None), Ok(true))
+    }
+
+    fn process_connect_four_move(
+        &self,
+        room: &mut GameRoom,
+        player: Player,
+        move_data: &MoveData,
+    ) -> (Result<bool, ()>, Result<Option<Player>, ()>, Result<bool, ()>) {
+        let col = move_data.primary as u8;
+
+        let board = match &mut room.connect_four_board {
+            Some(b) => b,
+            None => return (Err(()), Err(()), Err(())),
+        };
+
+        let row = board.drop_piece(col, player);
+        if row < 0 {
+            return (Err(()), Err(()), Err(())); // Invalid move
+        }
+
+        // Check for winner
+        if let Some(winner) = board.check_winner() {
+            return (Ok(true), Ok(Some(winner)), Ok(false));
+        }
+
+        // Check for draw
+        if board.is_full() {
+            return (Ok(true), Ok(None), Ok(false));
+        }
+
+        (Ok(false), Ok(None), Ok(true))
+    }
+
+    fn process_reversi_move(
+        &self,
         room: &mut GameRoom,
         player: Player,
         move_data: &MoveData,
@@ -656,34 +2044,62 @@ impl ChainCyclesContract {
             None => return (Err(()), Err(()), Err(())),
         };
 
-        // Setup phase - place ships
+        // Setup phase - commit to a fleet
         if board.setup_phase {
-            let ship_data = match &move_data.secondary {
+            let commitment = match &move_data.secondary {
                 Some(s) => s,
                 None => return (Err(()), Err(()), Err(())),
             };
 
-            if !board.place_ships(player, ship_data) {
+            if !board.commit_fleet(player, commitment) {
                 return (Err(()), Err(()), Err(()));
             }
 
-            // During setup, don't switch turns (both players place simultaneously)
-            // Game starts when both are ready
-            let game_started = !board.setup_phase;
-            return (Ok(false), Ok(None), Ok(game_started));
+            // Players commit in turn (Player One first), then Player One
+            // opens the attack phase once both are ready
+            return (Ok(false), Ok(None), Ok(true));
         }
 
-        // Attack phase
-        let pos = move_data.primary as u8;
-        let (hit, _sunk) = board.attack(player, pos);
-        if !hit && board.moves.last() != Some(&pos) {
-            // Attack failed but wasn't recorded - invalid
-            return (Err(()), Err(()), Err(()));
+        // Reveal phase - open the commitment; the game ends after both reveals
+        if board.reveal_phase {
+            let opening = match &move_data.secondary {
+                Some(s) => s,
+                None => return (Err(()), Err(()), Err(())),
+            };
+
+            if !board.reveal(player, opening) {
+                return (Err(()), Err(()), Err(()));
+            }
+
+            return match board.result() {
+                Some((winner, reason)) => {
+                    if winner.is_some() && reason == "false_answers" {
+                        room.status = GameStatus::Forfeited;
+                    }
+                    room.end_reason = Some(reason.to_string());
+                    (Ok(true), Ok(winner), Ok(false))
+                }
+                None => (Ok(false), Ok(None), Ok(true)),
+            };
         }
 
-        // Check for winner
-        if let Some(winner) = board.check_winner() {
-            return (Ok(true), Ok(Some(winner)), Ok(false));
+        let pos = match u8::try_from(move_data.primary) {
+            Ok(p) => p,
+            Err(_) => return (Err(()), Err(()), Err(())),
+        };
+
+        // Defender answers the pending attack, then fires next
+        if board.pending_attack.is_some() {
+            let answer = move_data.secondary.as_deref().unwrap_or_default();
+            if !board.answer(player, pos, answer) {
+                return (Err(()), Err(()), Err(()));
+            }
+            return (Ok(false), Ok(None), Ok(false));
+        }
+
+        // Attack - the defender's chain answers
+        if !board.attack(player, pos) {
+            return (Err(()), Err(()), Err(()));
         }
 
         (Ok(false), Ok(None), Ok(true))
@@ -719,59 +2135,337 @@ impl ChainCyclesContract {
     }
 
     // ========================================================================
-    // REWARDS
+    // DISPUTES
     // ========================================================================
 
-    async fn distribute_rewards(&mut self, room: &GameRoom) {
-        let (winner_xp, winner_coins, loser_xp, loser_coins) = match room.game_type {
-            GameType::Chess => (
-                Rewards::CHESS_WINNER_XP,
-                Rewards::CHESS_WINNER_COINS,
-                Rewards::CHESS_LOSER_XP,
-                Rewards::CHESS_LOSER_COINS,
-            ),
-            GameType::ConnectFour => (
-                Rewards::CONNECT_FOUR_WINNER_XP,
-                Rewards::CONNECT_FOUR_WINNER_COINS,
-                Rewards::CONNECT_FOUR_LOSER_XP,
-                Rewards::CONNECT_FOUR_LOSER_COINS,
-            ),
-            GameType::Reversi => (
-                Rewards::REVERSI_WINNER_XP,
-                Rewards::REVERSI_WINNER_COINS,
-                Rewards::REVERSI_LOSER_XP,
-                Rewards::REVERSI_LOSER_COINS,
-            ),
-            GameType::Gomoku => (
-                Rewards::GOMOKU_WINNER_XP,
-                Rewards::GOMOKU_WINNER_COINS,
-                Rewards::GOMOKU_LOSER_XP,
-                Rewards::GOMOKU_LOSER_COINS,
-            ),
-            GameType::Battleship => (
-                Rewards::BATTLESHIP_WINNER_XP,
-                Rewards::BATTLESHIP_WINNER_COINS,
-                Rewards::BATTLESHIP_LOSER_XP,
-                Rewards::BATTLESHIP_LOSER_COINS,
-            ),
-            GameType::Mancala => (
-                Rewards::MANCALA_WINNER_XP,
-                Rewards::MANCALA_WINNER_COINS,
-                Rewards::MANCALA_LOSER_XP,
-                Rewards::MANCALA_LOSER_COINS,
-            ),
+    /// Hub chain for disputes: the configured hub, or the chain that
+    /// created the application
+    fn hub_chain_id(&mut self) -> String {
+        match self.state.hub_chain_id.get().clone() {
+            Some(hub) => hub,
+            None => self.runtime.application_creator_chain_id().to_string(),
+        }
+    }
+
+    /// Player asks the hub to arbitrate; both chains submit their own copy
+    async fn handle_open_dispute(&mut self, owner: AccountOwner) -> ChainCyclesResponse {
+        let wallet_key = format!("{:?}", owner);
+
+        let room = match self.state.game_room.get().clone() {
+            Some(r) => r,
+            None => return ChainCyclesError::RoomNotFound.into_response(),
         };
 
-        for (i, chain_id_str) in room.player_chain_ids.iter().enumerate() {
-            let (xp, coins, is_winner) = match room.winner {
-                Some(w) if w.index() == i => (winner_xp, winner_coins, true),
-                Some(_) => (loser_xp, loser_coins, false),
-                None => (Rewards::DRAW_XP, Rewards::DRAW_COINS, false),
+        let player = match room.player_wallets.iter().position(|w| *w == wallet_key) {
+            Some(0) => Player::One,
+            Some(1) => Player::Two,
+            _ => return ChainCyclesError::NotInRoom.into_response(),
+        };
+
+        if room.player_chain_ids.len() < 2 {
+            return ChainCyclesError::NoOpponent.into_response();
+        }
+
+        self.submit_dispute(&room);
+        let opened_msg = Message::DisputeOpened {
+            room_id: room.room_id(),
+        };
+        self.send_to_opponent(&room, player, opened_msg);
+
+        ChainCyclesResponse::Success(SuccessResponse {
+            message: "Dispute submitted to hub".to_string(),
+        })
+    }
+
+    /// Send our copy of the room to its hub chain
+    fn submit_dispute(&mut self, room: &GameRoom) {
+        if let Ok(hub_chain) = ChainId::from_str(&room.hub_chain_id) {
+            let submission = Message::DisputeSubmission { room: room.clone() };
+            self.runtime
+                .prepare_message(submission)
+                .with_authentication()
+                .send_to(hub_chain);
+        }
+    }
+
+    /// Opponent opened a dispute; submit our side to the hub as well
+    async fn handle_dispute_opened(&mut self, room_id: String) {
+        let origin = self
+            .runtime
+            .message_origin_chain_id()
+            .map(|c| c.to_string());
+        let room = match self.state.game_room.get().clone() {
+            Some(r) if r.room_id() == room_id => r,
+            _ => {
+                self.record_rejection(format!("dispute for unknown room {}", room_id));
+                return;
+            }
+        };
+
+        if !origin
+            .as_ref()
+            .is_some_and(|o| room.player_chain_ids.contains(o))
+        {
+            self.record_rejection(format!("dispute opened by unexpected chain {:?}", origin));
+            return;
+        }
+
+        self.submit_dispute(&room);
+    }
+
+    /// Hub collects both players' copies of a room, then rules on it
+    async fn handle_dispute_submission(&mut self, room: GameRoom) {
+        let room_id = room.room_id();
+        let my_chain = self.runtime.chain_id().to_string();
+        let origin = self
+            .runtime
+            .message_origin_chain_id()
+            .map(|c| c.to_string());
+
+        let submitter = origin
+            .as_ref()
+            .and_then(|o| room.player_chain_ids.iter().position(|c| c == o));
+        let submitter = match submitter {
+            Some(i) if room.hub_chain_id == my_chain && room.player_chain_ids.len() == 2 => i,
+            _ => {
+                self.record_rejection(format!(
+                    "dispute submission for room {} from {:?}",
+                    room_id, origin
+                ));
+                return;
+            }
+        };
+
+        let other_key = format!("{}/{}", room_id, room.player_chain_ids[1 - submitter]);
+        let other = match self.state.disputes.get(&other_key).await.ok().flatten() {
+            Some(other) => other,
+            None => {
+                // Wait for the opponent's copy
+                let key = format!("{}/{}", room_id, room.player_chain_ids[submitter]);
+                let _ = self.state.disputes.insert(&key, room);
+                return;
+            }
+        };
+
+        // Both copies must describe the same game
+        let same_game =
+            bcs::to_bytes(&room.initial_state()).ok() == bcs::to_bytes(&other.initial_state()).ok();
+        if !same_game {
+            self.record_rejection(format!("dispute copies of room {} disagree", room_id));
+            return;
+        }
+        let _ = self.state.disputes.remove(&other_key);
+
+        let copies = if submitter == 0 {
+            [room, other]
+        } else {
+            [other, room]
+        };
+        let now = self.runtime.system_time().micros();
+        let verdict = self.rule_dispute(&copies, now);
+
+        let _ = self
+            .state
+            .dispute_verdicts
+            .insert(&room_id, verdict.clone());
+        for chain_str in &copies[0].player_chain_ids {
+            if let Ok(player_chain) = ChainId::from_str(chain_str) {
+                let ruling = Message::DisputeRuling {
+                    verdict: verdict.clone(),
+                };
+                self.runtime
+                    .prepare_message(ruling)
+                    .with_authentication()
+                    .send_to(player_chain);
+            }
+        }
+    }
+
+    /// Replay a disputed game from the start, taking each player's moves
+    /// from that player's own chain; an illegal move forfeits the game
+    fn rule_dispute(&self, copies: &[GameRoom; 2], now: u64) -> DisputeVerdict {
+        let mut room = copies[0].initial_state();
+
+        while room.status == GameStatus::InProgress {
+            let player = room.current_turn;
+            let record = match copies[player.index()].move_log.get(room.move_log.len()) {
+                Some(r) if r.player == player => r,
+                _ => break,
+            };
+
+            if let Err(e) = self.apply_move(&mut room, player, &record.move_data, record.timestamp)
+            {
+                let reason = match e {
+                    ChainCyclesError::TimeExpired => "timeout",
+                    _ => "illegal_move",
+                };
+                room.status = GameStatus::Forfeited;
+                room.winner = Some(player.other());
+                room.end_reason = Some(reason.to_string());
+                break;
+            }
+        }
+
+        // A player's own chain may always concede the game
+        if room.status == GameStatus::InProgress {
+            let concession = [Player::One, Player::Two].into_iter().find(|p| {
+                let copy = &copies[p.index()];
+                copy.is_finished() && copy.winner == Some(p.other())
+            });
+            if let Some(loser) = concession {
+                room.status = GameStatus::Forfeited;
+                room.winner = Some(loser.other());
+                room.end_reason = copies[loser.index()].end_reason.clone();
+            }
+        }
+
+        // A timeout claim stands if the player to move had run out of time
+        // by the time it was made (counting vacation their own chain recorded)
+        if room.status == GameStatus::InProgress {
+            let loser = room.current_turn;
+            let claim = &copies[loser.other().index()];
+            let own = &copies[loser.index()];
+            let mut clocked = room.clone();
+            if own.sequence == room.sequence && own.clocks.len() == room.clocks.len() {
+                clocked.clocks = own.clocks.clone();
+            }
+            if claim.end_reason.as_deref() == Some("timeout")
+                && claim.winner == Some(loser.other())
+                && clocked.time_left(loser, claim.last_move_at) == Some(0)
+            {
+                room.status = GameStatus::Forfeited;
+                room.winner = Some(loser.other());
+                room.end_reason = Some("timeout".to_string());
+            }
+        }
+
+        // An agreed draw stands if both chains recorded it
+        let agreed = copies
+            .iter()
+            .all(|c| c.end_reason.as_deref() == Some("draw_agreed"));
+        if room.status == GameStatus::InProgress && agreed {
+            room.status = GameStatus::Draw;
+            room.end_reason = Some("draw_agreed".to_string());
+        }
+
+        // A claimed chess draw is not a move; accept it if the position allows it
+        if room.status == GameStatus::InProgress {
+            let claim = room.chess_board.as_ref().and_then(|b| b.claimable_draw());
+            if let Some(claim) = claim {
+                let reason = claim.reason().to_string();
+                if copies
+                    .iter()
+                    .any(|c| c.end_reason.as_ref() == Some(&reason))
+                {
+                    room.status = GameStatus::Draw;
+                    room.end_reason = Some(reason);
+                }
+            }
+        }
+
+        let end_reason = if room.status == GameStatus::InProgress {
+            None
+        } else {
+            let fallback = if room.winner.is_some() {
+                "finished"
+            } else {
+                "draw"
             };
+            Some(
+                room.end_reason
+                    .clone()
+                    .unwrap_or_else(|| fallback.to_string()),
+            )
+        };
+
+        DisputeVerdict {
+            room_id: room.room_id(),
+            status: room.status,
+            winner: room.winner,
+            end_reason,
+            move_log: room.move_log,
+            state_hash: room.state_hash,
+            decided_at: now,
+        }
+    }
+
+    /// Adopt the hub's ruling: replay its moves and settle the result
+    async fn handle_dispute_ruling(&mut self, verdict: DisputeVerdict) {
+        let origin = self
+            .runtime
+            .message_origin_chain_id()
+            .map(|c| c.to_string());
+        let room = match self.state.game_room.get().clone() {
+            Some(r)
+                if r.room_id() == verdict.room_id && origin.as_ref() == Some(&r.hub_chain_id) =>
+            {
+                r
+            }
+            _ => {
+                self.record_rejection(format!(
+                    "dispute ruling for room {} from {:?}",
+                    verdict.room_id, origin
+                ));
+                return;
+            }
+        };
+
+        let mut ruled = room.initial_state();
+        for record in &verdict.move_log {
+            if let Err(e) = self.apply_move(
+                &mut ruled,
+                record.player,
+                &record.move_data,
+                record.timestamp,
+            ) {
+                self.record_rejection(format!(
+                    "dispute ruling move {} does not replay: {:?}",
+                    record.sequence, e
+                ));
+                return;
+            }
+        }
+
+        if let Some(reason) = &verdict.end_reason {
+            ruled.winner = verdict.winner;
+            ruled.status = verdict.status;
+            ruled.end_reason = Some(reason.clone());
+        }
+
+        self.state.game_room.set(Some(ruled.clone()));
+        self.state.room_divergence.set(None);
+        let _ = self
+            .state
+            .dispute_verdicts
+            .insert(&verdict.room_id, verdict.clone());
+
+        // Pay (or correct) the local player's reward for the ruled result;
+        // if the game is still undecided, take back anything paid for it
+        let my_chain = self.runtime.chain_id().to_string();
+        if let Some(i) = ruled.player_chain_ids.iter().position(|c| *c == my_chain) {
+            let wallet = ruled.player_wallets[i].clone();
+            if ruled.is_finished() {
+                let (xp, coins, _) = Rewards::for_player(&ruled, i);
+                self.settle_reward(&verdict.room_id, &wallet, xp, coins, true)
+                    .await;
+            } else {
+                self.reverse_reward(&verdict.room_id, &wallet).await;
+            }
+        }
+    }
+
+    // ========================================================================
+    // REWARDS
+    // ========================================================================
+
+    async fn distribute_rewards(&mut self, room: &GameRoom) {
+        for (i, chain_id_str) in room.player_chain_ids.iter().enumerate() {
+            let (xp, coins, is_winner) = Rewards::for_player(room, i);
 
             // Send reward sync to player's chain
             if let Ok(player_chain) = ChainId::from_str(chain_id_str) {
                 let reward_msg = Message::RewardSync {
+                    room_id: room.room_id(),
                     player_wallet: room.player_wallets[i].clone(),
                     xp_earned: xp,
                     coins_earned: coins,
@@ -785,6 +2479,142 @@ impl ChainCyclesContract {
         }
     }
 
+    /// Apply a reward only if it matches a finished room on this chain
+    ///
+    /// The sender must be one of the room's chains, the message must be
+    /// signed by one of its players, the wallet must be the player on this
+    /// chain, and each room pays out at most once. Amounts are capped at
+    /// what `Rewards` grants for the locally recorded result.
+    async fn handle_reward_sync(
+        &mut self,
+        room_id: String,
+        player_wallet: String,
+        xp_earned: u64,
+        coins_earned: u64,
+        is_winner: bool,
+    ) {
+        // The room may already have been cleared while the reward was in flight
+        let room = match self.state.game_room.get().clone() {
+            Some(r) if r.room_id() == room_id && r.is_finished() => Some(r),
+            _ => self.state.unpaid_rooms.get(&room_id).await.ok().flatten(),
+        };
+        let room = match room {
+            Some(r) => r,
+            None => {
+                self.record_rejection(format!("reward for unknown room {}", room_id));
+                return;
+            }
+        };
+
+        let origin = self
+            .runtime
+            .message_origin_chain_id()
+            .map(|c| c.to_string());
+        let signer = self
+            .runtime
+            .authenticated_signer()
+            .map(|owner| format!("{:?}", owner));
+        let from_room_chain = origin
+            .as_ref()
+            .is_some_and(|o| room.player_chain_ids.contains(o));
+        let signed_by_player = signer
+            .as_ref()
+            .is_some_and(|s| room.player_wallets.contains(s));
+        if !from_room_chain || !signed_by_player {
+            self.record_rejection(format!(
+                "unauthenticated reward for room {} from {:?}",
+                room_id, origin
+            ));
+            return;
+        }
+
+        let my_chain = self.runtime.chain_id().to_string();
+        let index = match room.player_wallets.iter().position(|w| *w == player_wallet) {
+            Some(i) if room.player_chain_ids.get(i) == Some(&my_chain) => i,
+            _ => {
+                self.record_rejection(format!(
+                    "reward for room {} names a player not on this chain",
+                    room_id
+                ));
+                return;
+            }
+        };
+
+        let (max_xp, max_coins, won) = Rewards::for_player(&room, index);
+        if is_winner != won {
+            self.record_rejection(format!("reward for room {} has the wrong result", room_id));
+            return;
+        }
+
+        let paid = self
+            .settle_reward(
+                &room_id,
+                &player_wallet,
+                xp_earned.min(max_xp),
+                coins_earned.min(max_coins),
+                false,
+            )
+            .await;
+        if paid {
+            let _ = self.state.unpaid_rooms.remove(&room_id);
+        } else {
+            self.record_rejection(format!("duplicate reward for room {}", room_id));
+        }
+    }
+
+    /// Pay a player's reward for a room at most once; a hub ruling may
+    /// `correct` an earlier payout to match its verdict
+    async fn settle_reward(
+        &mut self,
+        room_id: &str,
+        wallet: &str,
+        xp: u64,
+        coins: u64,
+        correct: bool,
+    ) -> bool {
+        let claim_key = format!("{}/{}", room_id, wallet);
+        let paid = match self.state.claimed_rewards.get(&claim_key).await {
+            Ok(paid) => paid,
+            Err(_) => return false,
+        };
+
+        match paid {
+            None => self.apply_rewards(wallet, xp, coins).await,
+            Some(_) if !correct => return false,
+            Some(payout) => {
+                if let Ok(Some(mut profile)) = self.state.players.get(&wallet.to_string()).await {
+                    profile.xp = profile.xp.saturating_sub(payout.xp) + xp;
+                    profile.coins = profile.coins.saturating_sub(payout.coins) + coins;
+                    let _ = self.state.players.insert(&wallet.to_string(), profile);
+                }
+            }
+        }
+
+        let _ = self
+            .state
+            .claimed_rewards
+            .insert(&claim_key, RewardPayout { xp, coins });
+        true
+    }
+
+    /// Undo the payout for a room that turned out not to be finished, so it
+    /// can be paid normally once the game really ends
+    async fn reverse_reward(&mut self, room_id: &str, wallet: &str) {
+        let claim_key = format!("{}/{}", room_id, wallet);
+        let payout = match self.state.claimed_rewards.get(&claim_key).await {
+            Ok(Some(payout)) => payout,
+            _ => return,
+        };
+
+        if let Ok(Some(mut profile)) = self.state.players.get(&wallet.to_string()).await {
+            profile.xp = profile.xp.saturating_sub(payout.xp);
+            profile.coins = profile.coins.saturating_sub(payout.coins);
+            profile.total_games = profile.total_games.saturating_sub(1);
+            let _ = self.state.players.insert(&wallet.to_string(), profile);
+        }
+        let _ = self.state.claimed_rewards.remove(&claim_key);
+    }
+
     async fn apply_rewards(&mut self, wallet: &str, xp: u64, coins: u64) {
         if let Ok(Some(mut profile)) = self.state.players.get(&wallet.to_string()).await {
             profile.xp += xp;
@@ -4,9 +4,11 @@
 #![allow(clippy::large_enum_variant)]
 
 use async_graphql::{Enum, InputObject, SimpleObject, Union};
+use linera_sdk::bcs;
 use linera_sdk::graphql::GraphQLMutationRoot;
 use linera_sdk::linera_base_types::{AccountOwner, ChainId, ContractAbi, ServiceAbi, Timestamp};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 
 /// ABI definition for ChainCycles application
 pub struct ChainCyclesAbi;
@@ -89,6 +91,77 @@ pub enum GameStatus {
     Abandoned,
 }
 
+// ============================================================================
+// TIME CONTROL
+// ============================================================================
+
+/// How the per-move bonus of a time control is applied
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum, Default)]
+#[graphql(rename_items = "PascalCase")]
+pub enum ClockMode {
+    /// Bonus is added to the clock after each move
+    #[default]
+    Fischer,
+    /// Clock only starts running once the bonus has elapsed
+    Delay,
+    /// Correspondence: `base_secs` (e.g. N days) for every move, the clock
+    /// resets after each move and vacation days extend it
+    Correspondence,
+}
+
+/// Clock settings chosen when a room is created
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject, InputObject, Default)]
+#[graphql(input_name = "TimeControlInput")]
+pub struct TimeControl {
+    /// Starting time per player in seconds (seconds per move in
+    /// correspondence rooms: 86400 per day, e.g. 259200 for three days)
+    pub base_secs: u64,
+    /// Fischer increment or per-move delay in seconds
+    pub bonus_secs: u64,
+    pub mode: ClockMode,
+}
+
+impl TimeControl {
+    const MICROS: u64 = 1_000_000;
+    pub const DAY_MICROS: u64 = 86_400 * Self::MICROS;
+    /// Longest starting clock (or time per move) a room may use: 30 days
+    pub const MAX_BASE_SECS: u64 = 30 * 86_400;
+    /// Longest increment or delay a room may use: one hour
+    pub const MAX_BONUS_SECS: u64 = 3_600;
+
+    pub fn base_micros(&self) -> u64 {
+        self.base_secs.saturating_mul(Self::MICROS)
+    }
+
+    /// Free time at the start of each move before the clock runs
+    pub fn grace_micros(&self) -> u64 {
+        match self.mode {
+            ClockMode::Delay => self.bonus_secs.saturating_mul(Self::MICROS),
+            ClockMode::Fischer | ClockMode::Correspondence => 0,
+        }
+    }
+
+    /// Clock time used by a move that took `elapsed` microseconds
+    pub fn charge(&self, elapsed: u64) -> u64 {
+        elapsed.saturating_sub(self.grace_micros())
+    }
+
+    /// Mover's clock after a move that left `left` on it
+    pub fn after_move(&self, left: u64) -> u64 {
+        match self.mode {
+            ClockMode::Fischer => {
+                left.saturating_add(self.bonus_secs.saturating_mul(Self::MICROS))
+            }
+            ClockMode::Delay => left,
+            ClockMode::Correspondence => self.base_micros(),
+        }
+    }
+
+    pub fn is_correspondence(&self) -> bool {
+        self.mode == ClockMode::Correspondence
+    }
+}
+
 // ============================================================================
 // MOVE DATA - Unified input for all games
 // ============================================================================
@@ -101,13 +174,15 @@ pub struct MoveData {
     /// - ConnectFour: column (0-6)
     /// - Reversi: position (0-63)
     /// - Gomoku: position (0-224)
-    /// - Battleship: position (0-99) 
+    /// - Battleship: attack or answered position (0-99)
     /// - Mancala: pit index (0-5)
     /// - Chess: 0 (unused, use secondary)
     pub primary: i32,
     /// Secondary move data:
-    /// - Chess: move in UCI notation (e.g., "e2e4")
-    /// - Battleship setup: ship placement string
+    /// - Chess: move in UCI ("e2e4", "e7e8q") or SAN ("Nf3", "O-O", "e8=Q+")
+    /// - Battleship setup: fleet commitment (see `BattleshipBoard::fleet_commitment`)
+    /// - Battleship answer: "miss", "hit" or "sunk" (primary = attacked position)
+    /// - Battleship reveal: "salt:placement"
     /// - Others: unused
     pub secondary: Option<String>,
 }
@@ -128,6 +203,10 @@ pub struct ChessBoard {
     pub white_turn: bool,
     /// Castling rights: [white_kingside, white_queenside, black_kingside, black_queenside]
     pub castling: Vec<bool>,
+    /// Castling rook files (0 = a-file .. 7 = h-file), in the same order as `castling`
+    pub castling_rook_files: Vec<u8>,
+    /// Chess960 (Fischer Random) game: castling is entered as king-takes-rook (e.g., "b1a1")
+    pub chess960: bool,
     /// En passant target square index (-1 if none)
     pub en_passant: i8,
     /// Halfmove clock for 50-move rule
@@ -136,8 +215,14 @@ pub struct ChessBoard {
     pub fullmove: u16,
     /// Move history in UCI notation
     pub moves: Vec<String>,
+    /// Move history in Standard Algebraic Notation (parallel to `moves`)
+    pub san_moves: Vec<String>,
     /// Current FEN notation
     pub fen: String,
+    /// FEN of the position the game started from
+    pub initial_fen: String,
+    /// Position keys (FEN without clocks) after every move, for repetition detection
+    pub position_history: Vec<String>,
 }
 
 impl ChessBoard {
@@ -154,16 +239,257 @@ impl ChessBoard {
             "R".into(), "N".into(), "B".into(), "Q".into(), "K".into(), "B".into(), "N".into(), "R".into(), // Row 1 (a1-h1)
         ];
         
-        Self {
+        let mut chess = Self {
             board,
             white_turn: true,
             castling: vec![true, true, true, true],
+            castling_rook_files: CLASSICAL_ROOK_FILES.to_vec(),
+            chess960: false,
             en_passant: -1,
             halfmove: 0,
             fullmove: 1,
             moves: Vec::new(),
+            san_moves: Vec::new(),
             fen: "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1".to_string(),
+            initial_fen: "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1".to_string(),
+            position_history: Vec::new(),
+        };
+        chess.position_history.push(chess.position_key());
+        chess
+    }
+
+    /// Create a Chess960 starting position from its Scharnagl number
+    /// (0-959, where 518 is the classical setup)
+    pub fn new_chess960(index: u16) -> Self {
+        let back_rank = chess960_back_rank(index);
+        let black: String = back_rank.iter().collect();
+        let white = black.to_ascii_uppercase();
+
+        // Shredder-FEN castling field naming both rook files for each side
+        let rook_files: Vec<char> = back_rank
+            .iter()
+            .enumerate()
+            .filter(|(_, &p)| p == 'r')
+            .map(|(file, _)| (b'a' + file as u8) as char)
+            .collect();
+        let castling = format!(
+            "{}{}{}{}",
+            rook_files[1].to_ascii_uppercase(),
+            rook_files[0].to_ascii_uppercase(),
+            rook_files[1],
+            rook_files[0]
+        );
+
+        let fen = format!(
+            "{}/pppppppp/8/8/8/8/PPPPPPPP/{} w {} - 0 1",
+            black, white, castling
+        );
+        let mut chess = Self::from_fen(&fen).expect("Chess960 setup is always valid");
+        chess.chess960 = true;
+        chess
+    }
+
+    /// Parse and validate a FEN string into a board
+    ///
+    /// The halfmove and fullmove clocks may be omitted, in which case they
+    /// default to "0 1".
+    pub fn from_fen(fen: &str) -> Result<Self, String> {
+        let fields: Vec<&str> = fen.split_whitespace().collect();
+        if fields.len() != 4 && fields.len() != 6 {
+            return Err("FEN must have 4 or 6 fields".to_string());
+        }
+
+        // Piece placement
+        let ranks: Vec<&str> = fields[0].split('/').collect();
+        if ranks.len() != 8 {
+            return Err("piece placement must have 8 ranks".to_string());
+        }
+        let mut board = Vec::with_capacity(64);
+        for rank in &ranks {
+            let mut files = 0;
+            for c in rank.chars() {
+                if let Some(empty) = c.to_digit(10) {
+                    if !(1..=8).contains(&empty) {
+                        return Err(format!("invalid empty square count '{}'", c));
+                    }
+                    files += empty;
+                    board.extend((0..empty).map(|_| " ".to_string()));
+                } else if "pnbrqkPNBRQK".contains(c) {
+                    files += 1;
+                    board.push(c.to_string());
+                } else {
+                    return Err(format!("invalid piece '{}'", c));
+                }
+            }
+            if files != 8 {
+                return Err(format!("rank '{}' does not have 8 files", rank));
+            }
         }
+
+        // Side to move
+        let white_turn = match fields[1] {
+            "w" => true,
+            "b" => false,
+            other => return Err(format!("invalid side to move '{}'", other)),
+        };
+
+        // Castling rights: KQkq, or rook files as in Shredder-FEN/X-FEN (e.g., "HAha", "Bk")
+        let mut castling = vec![false; 4];
+        let mut castling_rook_files = CLASSICAL_ROOK_FILES.to_vec();
+        let mut chess960 = false;
+        if fields[2] != "-" {
+            for c in fields[2].chars() {
+                let white = c.is_ascii_uppercase();
+                let back_row = if white { 7 } else { 0 };
+                let (king, rook) = if white { ("K", "R") } else { ("k", "r") };
+                let king_file = match (0..8).find(|&f| board[back_row * 8 + f] == king) {
+                    Some(f) => f as u8,
+                    None => return Err(format!("castling right '{}' without king on back rank", c)),
+                };
+                let is_rook = |file: u8| board[back_row * 8 + file as usize] == rook;
+
+                let rook_file = match c.to_ascii_lowercase() {
+                    // Outermost rook on the king's side
+                    'k' => (king_file + 1..8).rev().find(|&f| is_rook(f)),
+                    'q' => (0..king_file).find(|&f| is_rook(f)),
+                    f @ 'a'..='h' => Some(f as u8 - b'a'),
+                    _ => return Err(format!("invalid castling right '{}'", c)),
+                };
+                let rook_file = match rook_file {
+                    Some(f) if f != king_file => f,
+                    _ => return Err(format!("castling right '{}' has no rook", c)),
+                };
+
+                let right = match (white, rook_file > king_file) {
+                    (true, true) => 0,
+                    (true, false) => 1,
+                    (false, true) => 2,
+                    (false, false) => 3,
+                };
+                if castling[right] {
+                    return Err(format!("duplicate castling right '{}'", c));
+                }
+                castling[right] = true;
+                castling_rook_files[right] = rook_file;
+
+                // Anything other than e-file king with corner rooks needs Chess960 castling
+                if king_file != 4 || rook_file != CLASSICAL_ROOK_FILES[right] {
+                    chess960 = true;
+                }
+            }
+        }
+
+        // En passant target
+        let en_passant = if fields[3] == "-" {
+            -1
+        } else {
+            match parse_square(fields[3]) {
+                Some(sq) => sq as i8,
+                None => return Err(format!("invalid en passant square '{}'", fields[3])),
+            }
+        };
+
+        // Clocks
+        let (halfmove, fullmove) = if fields.len() == 6 {
+            let halfmove = fields[4]
+                .parse::<u16>()
+                .map_err(|_| format!("invalid halfmove clock '{}'", fields[4]))?;
+            let fullmove = fields[5]
+                .parse::<u16>()
+                .ok()
+                .filter(|&n| n >= 1)
+                .ok_or_else(|| format!("invalid fullmove number '{}'", fields[5]))?;
+            (halfmove, fullmove)
+        } else {
+            (0, 1)
+        };
+
+        let mut chess = Self {
+            board,
+            white_turn,
+            castling,
+            castling_rook_files,
+            chess960,
+            en_passant,
+            halfmove,
+            fullmove,
+            moves: Vec::new(),
+            san_moves: Vec::new(),
+            fen: String::new(),
+            initial_fen: String::new(),
+            position_history: Vec::new(),
+        };
+        chess.validate_position()?;
+
+        chess.update_fen();
+        chess.initial_fen = chess.fen.clone();
+        chess.position_history.push(chess.position_key());
+        Ok(chess)
+    }
+
+    /// Check that a parsed position could arise in a real game
+    fn validate_position(&self) -> Result<(), String> {
+        for white in [true, false] {
+            let side = if white { "white" } else { "black" };
+            let own: Vec<char> = (0..64)
+                .map(|idx| self.get_piece(idx))
+                .filter(|&p| ChessPosition::is_own(p, white))
+                .collect();
+
+            let kings = own.iter().filter(|p| p.eq_ignore_ascii_case(&'k')).count();
+            if kings != 1 {
+                return Err(format!("{} must have exactly one king", side));
+            }
+            let pawns = own.iter().filter(|p| p.eq_ignore_ascii_case(&'p')).count();
+            if pawns > 8 || own.len() > 16 {
+                return Err(format!("{} has too many pieces", side));
+            }
+        }
+
+        if (0..8).chain(56..64).any(|idx| self.get_piece(idx).eq_ignore_ascii_case(&'p')) {
+            return Err("pawns cannot stand on the first or last rank".to_string());
+        }
+
+        // Castling rights need the king on its back rank and the rook on its castling square
+        let position = self.position();
+        for right in 0..4 {
+            if !position.castling[right] {
+                continue;
+            }
+            let white = right < 2;
+            let rook = if white { 'R' } else { 'r' };
+            let back_row = if white { 7 } else { 0 };
+            let king_on_back_rank = position
+                .king_square(white)
+                .is_some_and(|k| k / 8 == back_row);
+            if !king_on_back_rank || position.squares[position.castling_rooks[right]] != rook {
+                return Err("castling rights do not match king and rook placement".to_string());
+            }
+        }
+
+        // En passant square must sit behind a pawn that just made a double push
+        if self.en_passant >= 0 {
+            let ep = self.en_passant as usize;
+            let (ep_row, pawn_sq, origin_sq, pawn) = if self.white_turn {
+                (2, ep + 8, ep.wrapping_sub(8), 'p')
+            } else {
+                (5, ep.wrapping_sub(8), ep + 8, 'P')
+            };
+            if ep / 8 != ep_row
+                || self.get_piece(ep) != ' '
+                || self.get_piece(origin_sq) != ' '
+                || self.get_piece(pawn_sq) != pawn
+            {
+                return Err("en passant square is not consistent with the position".to_string());
+            }
+        }
+
+        // The side that just moved cannot have left its king in check
+        if position.in_check(!self.white_turn) {
+            return Err("side not to move is in check".to_string());
+        }
+
+        Ok(())
     }
 
     /// Get piece at position
@@ -180,148 +506,562 @@ impl ChessBoard {
         }
     }
 
-    /// Parse UCI move string (e.g., "e2e4") and apply it
+    /// Parse UCI move string (e.g., "e2e4", "e7e8q") and apply it if it is legal
     pub fn make_move(&mut self, uci_move: &str, is_white: bool) -> bool {
-        if uci_move.len() < 4 {
-            return false;
+        match ChessMove::from_uci(uci_move) {
+            Some(chess_move) => self.make_chess_move(&chess_move, is_white),
+            None => false,
         }
+    }
 
-        // Verify turn
-        if self.white_turn != is_white {
-            return false;
-        }
+    /// Parse a move given in either UCI ("g1f3") or SAN ("Nf3") notation
+    pub fn parse_move(&self, notation: &str) -> Option<ChessMove> {
+        ChessMove::from_uci(notation)
+            .map(|m| self.normalize_castling(m))
+            .or_else(|| self.parse_san(notation))
+    }
 
-        let chars: Vec<char> = uci_move.chars().collect();
-        let from_file = (chars[0] as u8).wrapping_sub(b'a') as i32;
-        let from_rank = (chars[1] as u8).wrapping_sub(b'1') as i32;
-        let to_file = (chars[2] as u8).wrapping_sub(b'a') as i32;
-        let to_rank = (chars[3] as u8).wrapping_sub(b'1') as i32;
+    /// Convert classical UCI castling ("e1g1") into the internal king-takes-rook form
+    ///
+    /// Chess960 games only accept king-takes-rook, since there the king may
+    /// legitimately move to a neighbouring square or not move at all.
+    fn normalize_castling(&self, chess_move: ChessMove) -> ChessMove {
+        let from = chess_move.from as usize;
+        let to = chess_move.to as usize;
+        let piece = self.get_piece(from);
+        let is_two_file_king_move = piece.eq_ignore_ascii_case(&'k')
+            && from / 8 == to / 8
+            && (to % 8).abs_diff(from % 8) == 2;
+        if self.chess960 || !is_two_file_king_move {
+            return chess_move;
+        }
 
-        if from_file < 0 || from_file > 7 || from_rank < 0 || from_rank > 7 
-            || to_file < 0 || to_file > 7 || to_rank < 0 || to_rank > 7 {
-            return false;
+        let right = match (piece == 'K', to > from) {
+            (true, true) => 0,
+            (true, false) => 1,
+            (false, true) => 2,
+            (false, false) => 3,
+        };
+        ChessMove {
+            from: chess_move.from,
+            to: self.position().castling_rooks[right] as u8,
+            promotion: None,
         }
+    }
 
-        // Convert to board indices (a8=0, h1=63)
-        let from_idx = ((7 - from_rank) * 8 + from_file) as usize;
-        let to_idx = ((7 - to_rank) * 8 + to_file) as usize;
+    /// Format a move in UCI, using "e1g1"-style castling outside Chess960
+    pub fn move_to_uci(&self, chess_move: &ChessMove) -> String {
+        let position = self.position();
+        if !self.chess960 && position.is_castling(chess_move) {
+            let row = chess_move.from / 8;
+            let king_to = row * 8 + if chess_move.to > chess_move.from { 6 } else { 2 };
+            return ChessMove { to: king_to, ..*chess_move }.to_uci();
+        }
+        chess_move.to_uci()
+    }
 
-        let piece = self.get_piece(from_idx);
-        if piece == ' ' {
+    /// Apply a parsed move if it is legal for the given side
+    ///
+    /// Pawn moves to the last rank must name their promotion piece, and
+    /// no other move may carry one.
+    pub fn make_chess_move(&mut self, chess_move: &ChessMove, is_white: bool) -> bool {
+        // Verify turn
+        if self.white_turn != is_white {
             return false;
         }
 
-        // Verify piece belongs to current player
-        let piece_is_white = piece.is_uppercase();
-        if piece_is_white != is_white {
+        // Only moves produced by the legal move generator are accepted
+        let chess_move = self.normalize_castling(*chess_move);
+        if !self.legal_moves().contains(&chess_move) {
             return false;
         }
 
-        let captured = self.get_piece(to_idx);
+        self.apply_move(&chess_move);
+        true
+    }
 
-        // Handle castling
-        let piece_lower = piece.to_ascii_lowercase();
-        if piece_lower == 'k' {
-            let file_diff = to_file - from_file;
-            // Kingside castle
-            if file_diff == 2 {
-                let rook_from = from_idx + 3;
-                let rook_to = from_idx + 1;
-                self.set_piece(rook_to, self.get_piece(rook_from));
-                self.set_piece(rook_from, ' ');
-            }
-            // Queenside castle
-            else if file_diff == -2 {
-                let rook_from = from_idx - 4;
-                let rook_to = from_idx - 1;
-                self.set_piece(rook_to, self.get_piece(rook_from));
-                self.set_piece(rook_from, ' ');
-            }
-
-            // Remove castling rights
-            if is_white {
-                self.castling[0] = false;
-                self.castling[1] = false;
-            } else {
-                self.castling[2] = false;
-                self.castling[3] = false;
+    /// All legal moves for the side to move
+    pub fn legal_moves(&self) -> Vec<ChessMove> {
+        self.position().legal_moves()
+    }
+
+    /// Is the side to move currently in check?
+    pub fn is_in_check(&self) -> bool {
+        let position = self.position();
+        position.in_check(position.white_turn)
+    }
+
+    /// Count the leaf nodes of the legal move tree to `depth` plies
+    ///
+    /// Used to verify the move generator against published perft results.
+    pub fn perft(&self, depth: u32) -> u64 {
+        self.position().perft(depth)
+    }
+
+    /// Board indices attacked by the given side
+    pub fn attacked_squares(&self, by_white: bool) -> Vec<u8> {
+        let position = self.position();
+        (0..64u8)
+            .filter(|&idx| position.is_attacked(idx as usize, by_white))
+            .collect()
+    }
+
+    /// Detect a finished game for the side to move: checkmate, stalemate
+    /// or one of the automatic draws (dead position, 75 moves, fivefold repetition)
+    pub fn outcome(&self) -> Option<ChessOutcome> {
+        if self.legal_moves().is_empty() {
+            if self.is_in_check() {
+                // The side to move is mated, so the other side wins
+                let winner = if self.white_turn { Player::Two } else { Player::One };
+                return Some(ChessOutcome::Checkmate { winner });
             }
+            return Some(ChessOutcome::Stalemate);
+        }
+        if self.has_insufficient_material() {
+            return Some(ChessOutcome::InsufficientMaterial);
+        }
+        if self.halfmove >= 150 {
+            return Some(ChessOutcome::SeventyFiveMoveRule);
+        }
+        if self.repetition_count() >= 5 {
+            return Some(ChessOutcome::FivefoldRepetition);
         }
+        None
+    }
 
-        // Handle rook moves (castling rights)
-        if piece_lower == 'r' {
-            if is_white {
-                if from_idx == 63 { self.castling[0] = false; } // h1
-                if from_idx == 56 { self.castling[1] = false; } // a1
-            } else {
-                if from_idx == 7 { self.castling[2] = false; }  // h8
-                if from_idx == 0 { self.castling[3] = false; }  // a8
-            }
+    /// Draw that either player may claim in the current position
+    /// (fifty-move rule or threefold repetition)
+    pub fn claimable_draw(&self) -> Option<ChessOutcome> {
+        if self.repetition_count() >= 3 {
+            return Some(ChessOutcome::ThreefoldRepetition);
+        }
+        if self.halfmove >= 100 {
+            return Some(ChessOutcome::FiftyMoveRule);
+        }
+        None
+    }
+
+    /// How many times the current position has occurred (including now)
+    pub fn repetition_count(&self) -> usize {
+        let current = self.position_key();
+        self.position_history.iter().filter(|k| **k == current).count()
+    }
+
+    /// Key identifying a position for repetition: placement, side to move,
+    /// castling rights and en passant square (only when a capture is possible)
+    pub fn position_key(&self) -> String {
+        let fields: Vec<&str> = self.fen.split(' ').take(3).collect();
+        let mut key = fields.join(" ");
+        key.push(' ');
+
+        let ep = self.en_passant;
+        let ep_capturable = ep >= 0
+            && self.legal_moves().iter().any(|m| {
+                m.to as i8 == ep && self.get_piece(m.from as usize).eq_ignore_ascii_case(&'p')
+            });
+        if ep_capturable {
+            key.push_str(&square_name(ep as u8));
+        } else {
+            key.push('-');
         }
+        key
+    }
 
-        // Handle en passant capture
-        if piece_lower == 'p' && self.en_passant >= 0 {
-            if to_idx as i8 == self.en_passant {
-                // Capture en passant
-                let captured_pawn_idx = if is_white {
-                    to_idx + 8
-                } else {
-                    to_idx - 8
-                };
-                self.set_piece(captured_pawn_idx, ' ');
+    /// Neither side can possibly checkmate: bare kings, a single minor piece,
+    /// or only bishops that all stand on squares of the same colour
+    pub fn has_insufficient_material(&self) -> bool {
+        let mut minors = Vec::new();
+        for idx in 0..64 {
+            match self.get_piece(idx).to_ascii_lowercase() {
+                ' ' | 'k' => {}
+                'b' | 'n' => minors.push(idx),
+                _ => return false,
             }
         }
 
-        // Set en passant square for next move
-        self.en_passant = -1;
-        if piece_lower == 'p' {
-            let rank_diff = (to_rank - from_rank).abs();
-            if rank_diff == 2 {
-                // Double pawn push - set en passant square
-                self.en_passant = ((from_idx as i32 + to_idx as i32) / 2) as i8;
-            }
+        if minors.len() <= 1 {
+            return true;
         }
 
-        // Make the move
-        self.set_piece(to_idx, piece);
-        self.set_piece(from_idx, ' ');
+        let square_colour = |idx: usize| (idx / 8 + idx % 8) % 2;
+        let all_bishops = minors.iter().all(|&idx| self.get_piece(idx).eq_ignore_ascii_case(&'b'));
+        all_bishops && minors.iter().all(|&idx| square_colour(idx) == square_colour(minors[0]))
+    }
 
-        // Handle pawn promotion
-        if piece_lower == 'p' {
-            let promotion_rank = if is_white { 0 } else { 7 };
-            if to_rank == promotion_rank || (7 - to_rank) == promotion_rank {
-                let promo_piece = if uci_move.len() >= 5 {
-                    chars[4]
-                } else {
-                    'q' // Default to queen
-                };
-                let promo = if is_white { promo_piece.to_ascii_uppercase() } else { promo_piece.to_ascii_lowercase() };
-                self.set_piece(to_idx, promo);
-            }
-        }
+    /// Apply a move that has already been validated against `legal_moves`
+    fn apply_move(&mut self, chess_move: &ChessMove) {
+        let san = self.san(chess_move);
+        let uci = self.move_to_uci(chess_move);
+        let mut position = self.position();
+        let piece = position.squares[chess_move.from as usize];
+        let is_capture = position.is_capture(chess_move);
+        position.apply(chess_move);
+
+        self.board = position.squares.iter().map(|c| c.to_string()).collect();
+        self.castling = position.castling.to_vec();
+        self.en_passant = position.en_passant;
 
         // Update halfmove clock
-        if piece_lower == 'p' || captured != ' ' {
+        if piece.eq_ignore_ascii_case(&'p') || is_capture {
             self.halfmove = 0;
         } else {
             self.halfmove += 1;
         }
 
         // Update fullmove number
-        if !is_white {
+        if !self.white_turn {
             self.fullmove += 1;
         }
 
         // Switch turn
-        self.white_turn = !self.white_turn;
+        self.white_turn = position.white_turn;
 
         // Record move
-        self.moves.push(uci_move.to_string());
+        self.moves.push(uci);
+        self.san_moves.push(san);
 
         // Update FEN
         self.update_fen();
 
-        true
+        // Record position for repetition detection
+        self.position_history.push(self.position_key());
+    }
+
+    /// Build the compact position used by the move generator
+    fn position(&self) -> ChessPosition {
+        let mut squares = [' '; 64];
+        for (idx, square) in squares.iter_mut().enumerate() {
+            *square = self.get_piece(idx);
+        }
+        let mut castling = [false; 4];
+        let mut castling_rooks = [0; 4];
+        for i in 0..4 {
+            castling[i] = self.castling.get(i).copied().unwrap_or(false);
+            let back_row = if i < 2 { 7 } else { 0 };
+            castling_rooks[i] = back_row * 8 + self.rook_file(i) as usize;
+        }
+        ChessPosition {
+            squares,
+            white_turn: self.white_turn,
+            castling,
+            castling_rooks,
+            en_passant: self.en_passant,
+        }
+    }
+
+    /// Castling rook file for a right, falling back to the classical corners
+    fn rook_file(&self, right: usize) -> u8 {
+        self.castling_rook_files
+            .get(right)
+            .copied()
+            .unwrap_or(CLASSICAL_ROOK_FILES[right])
+    }
+
+    /// Format a legal move in Standard Algebraic Notation (e.g., "Nf3", "exd5", "O-O", "e8=Q+")
+    pub fn san(&self, chess_move: &ChessMove) -> String {
+        let position = self.position();
+        let from = chess_move.from as usize;
+        let to = chess_move.to as usize;
+        let piece = position.squares[from].to_ascii_lowercase();
+
+        let mut san = if position.is_castling(chess_move) {
+            if to > from { "O-O".to_string() } else { "O-O-O".to_string() }
+        } else {
+            let mut san = String::new();
+            let capture = position.is_capture(chess_move);
+            let target = square_name(chess_move.to);
+
+            if piece == 'p' {
+                if capture {
+                    san.push_str(&square_name(chess_move.from)[0..1]);
+                }
+            } else {
+                san.push(piece.to_ascii_uppercase());
+
+                // Disambiguate between identical pieces that can reach the same square
+                let rivals: Vec<ChessMove> = position
+                    .legal_moves()
+                    .into_iter()
+                    .filter(|m| {
+                        m.to == chess_move.to
+                            && m.from != chess_move.from
+                            && position.squares[m.from as usize] == position.squares[from]
+                    })
+                    .collect();
+                if !rivals.is_empty() {
+                    let from_name = square_name(chess_move.from);
+                    if rivals.iter().all(|m| m.from % 8 != chess_move.from % 8) {
+                        san.push_str(&from_name[0..1]);
+                    } else if rivals.iter().all(|m| m.from / 8 != chess_move.from / 8) {
+                        san.push_str(&from_name[1..2]);
+                    } else {
+                        san.push_str(&from_name);
+                    }
+                }
+            }
+
+            if capture {
+                san.push('x');
+            }
+            san.push_str(&target);
+            if let Some(p) = chess_move.promotion {
+                san.push('=');
+                san.push(p.to_ascii_uppercase());
+            }
+            san
+        };
+
+        // Check and checkmate suffixes
+        let mut next = position;
+        next.apply(chess_move);
+        if next.in_check(next.white_turn) {
+            san.push(if next.legal_moves().is_empty() { '#' } else { '+' });
+        }
+        san
+    }
+
+    /// Parse a SAN move (e.g., "Nbd7", "exd6", "O-O-O", "e8=Q#") against the legal moves
+    ///
+    /// Over-disambiguated ("Ngf3") and long algebraic ("Ng1-f3", "e2e4") forms
+    /// are accepted too, as long as they name exactly one legal move.
+    pub fn parse_san(&self, san: &str) -> Option<ChessMove> {
+        let normalized = san
+            .trim()
+            .trim_end_matches(['+', '#', '!', '?'])
+            .replace('0', "O");
+        let position = self.position();
+        let legal = position.legal_moves();
+
+        if normalized == "O-O" || normalized == "O-O-O" {
+            let kingside = normalized == "O-O";
+            return legal
+                .into_iter()
+                .find(|m| position.is_castling(m) && (m.to > m.from) == kingside);
+        }
+
+        // Piece letter, optional from-file/rank, destination, optional promotion
+        let marked_capture = normalized.contains(['x', ':']);
+        let mut body: Vec<char> = normalized
+            .chars()
+            .filter(|c| !matches!(c, 'x' | ':' | '-' | '='))
+            .collect();
+        let piece = match body.first() {
+            Some(&p @ ('K' | 'Q' | 'R' | 'B' | 'N')) => {
+                body.remove(0);
+                p.to_ascii_lowercase()
+            }
+            _ => 'p',
+        };
+        let promotion = match body.last() {
+            Some(&p) if "QRBNqrbn".contains(p) => {
+                body.pop();
+                Some(p.to_ascii_lowercase())
+            }
+            _ => None,
+        };
+        if body.len() < 2 || body.len() > 4 {
+            return None;
+        }
+        let (hint, target) = body.split_at(body.len() - 2);
+        let to = parse_square(&target.iter().collect::<String>())?;
+
+        let mut from_file = None;
+        let mut from_row = None;
+        for &c in hint {
+            match c {
+                'a'..='h' if from_file.is_none() && from_row.is_none() => {
+                    from_file = Some(c as u8 - b'a')
+                }
+                '1'..='8' if from_row.is_none() => from_row = Some(7 - (c as u8 - b'1')),
+                _ => return None,
+            }
+        }
+
+        let mut candidates = legal.into_iter().filter(|m| {
+            let moving = position.squares[m.from as usize].to_ascii_lowercase();
+            !position.is_castling(m)
+                && m.to == to
+                && m.promotion == promotion
+                && moving == piece
+                && (!marked_capture || position.is_capture(m))
+                // Pawn captures always name the file they come from
+                && !(piece == 'p' && position.is_capture(m) && from_file.is_none())
+                && from_file.is_none_or(|f| m.from % 8 == f)
+                && from_row.is_none_or(|r| m.from / 8 == r)
+        });
+        let chess_move = candidates.next()?;
+        candidates.next().is_none().then_some(chess_move)
+    }
+
+    /// Board set up at the starting position of this game
+    pub fn initial_board(&self) -> ChessBoard {
+        let mut initial =
+            ChessBoard::from_fen(&self.initial_fen).unwrap_or_else(|_| ChessBoard::new());
+        initial.chess960 |= self.chess960;
+        initial
+    }
+
+    /// Move history in SAN, rebuilt from the UCI history if `san_moves` is incomplete
+    pub fn san_history(&self) -> Vec<String> {
+        if self.san_moves.len() == self.moves.len() {
+            return self.san_moves.clone();
+        }
+
+        let mut replay = self.initial_board();
+        let mut history = Vec::with_capacity(self.moves.len());
+        for uci in &self.moves {
+            let chess_move = match replay.parse_move(uci) {
+                Some(m) => m,
+                None => break,
+            };
+            history.push(replay.san(&chess_move));
+            replay.apply_move(&chess_move);
+        }
+        history
+    }
+
+    /// Export the game as PGN with the given tag pairs and result ("1-0", "0-1", "1/2-1/2" or "*")
+    ///
+    /// SetUp/FEN tags are added automatically for games that did not start
+    /// from the standard position.
+    pub fn to_pgn(&self, tags: &[(&str, String)], result: &str) -> String {
+        let mut pgn = String::new();
+        for (name, value) in tags {
+            let escaped = value.replace('\\', "\\\\").replace('"', "\\\"");
+            pgn.push_str(&format!("[{} \"{}\"]\n", name, escaped));
+        }
+        let initial = self.initial_board();
+        if self.chess960 {
+            pgn.push_str("[Variant \"Chess960\"]\n");
+        }
+        if initial.fen != ChessBoard::new().fen {
+            pgn.push_str("[SetUp \"1\"]\n");
+            pgn.push_str(&format!("[FEN \"{}\"]\n", initial.fen));
+        }
+        pgn.push('\n');
+
+        // Movetext with move numbers, wrapped at 80 columns
+        let mut tokens = Vec::new();
+        let mut white_turn = initial.white_turn;
+        let mut fullmove = initial.fullmove;
+        for (i, san) in self.san_history().into_iter().enumerate() {
+            if white_turn {
+                tokens.push(format!("{}.", fullmove));
+            } else if i == 0 {
+                tokens.push(format!("{}...", fullmove));
+            }
+            tokens.push(san);
+            if !white_turn {
+                fullmove += 1;
+            }
+            white_turn = !white_turn;
+        }
+        tokens.push(result.to_string());
+
+        let mut line = String::new();
+        for token in tokens {
+            if !line.is_empty() && line.len() + 1 + token.len() > 80 {
+                pgn.push_str(&line);
+                pgn.push('\n');
+                line.clear();
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(&token);
+        }
+        pgn.push_str(&line);
+        pgn.push('\n');
+        pgn
+    }
+
+    /// Import a PGN game, replaying every move to validate it
+    ///
+    /// Comments, variations, NAGs and move numbers are skipped. The result
+    /// token, if present, must agree with a checkmate on the board.
+    pub fn from_pgn(pgn: &str) -> Result<Self, String> {
+        let mut fen = None;
+        let mut chess960 = false;
+        let mut movetext = String::new();
+        for line in pgn.lines() {
+            let line = line.trim();
+            if line.starts_with('[') {
+                let inner = line.trim_start_matches('[').trim_end_matches(']');
+                if let Some((name, value)) = inner.split_once(' ') {
+                    let value = value.trim().trim_matches('"');
+                    match name {
+                        "FEN" => fen = Some(value.to_string()),
+                        "Variant" => chess960 = value.eq_ignore_ascii_case("chess960"),
+                        _ => {}
+                    }
+                }
+            } else {
+                movetext.push_str(line);
+                movetext.push('\n');
+            }
+        }
+
+        let mut board = match fen {
+            Some(f) => ChessBoard::from_fen(&f)?,
+            None => ChessBoard::new(),
+        };
+        board.chess960 |= chess960;
+
+        // Strip comments and variations
+        let mut cleaned = String::new();
+        let mut brace = false;
+        let mut line_comment = false;
+        let mut depth = 0u32;
+        for c in movetext.chars() {
+            match c {
+                _ if line_comment => line_comment = c != '\n',
+                '{' if !brace => brace = true,
+                '}' if brace => brace = false,
+                _ if brace => {}
+                ';' => line_comment = true,
+                '(' => depth += 1,
+                ')' if depth > 0 => depth -= 1,
+                _ if depth > 0 => {}
+                _ => cleaned.push(c),
+            }
+        }
+
+        let mut result = None;
+        for token in cleaned.split_whitespace() {
+            if matches!(token, "1-0" | "0-1" | "1/2-1/2" | "*") {
+                result = Some(token.to_string());
+                continue;
+            }
+            if token.starts_with('$') {
+                continue;
+            }
+            // Drop move numbers such as "12." or "12..." (possibly glued to the move),
+            // leaving zero-style castling ("0-0") alone
+            let after_number = token.trim_start_matches(|c: char| c.is_ascii_digit());
+            let after_dots = after_number.trim_start_matches('.');
+            let san = if after_number.len() < token.len() && after_dots.len() < after_number.len() {
+                after_dots
+            } else {
+                token
+            };
+            if san.is_empty() {
+                continue;
+            }
+            let chess_move = board
+                .parse_san(san)
+                .ok_or_else(|| format!("illegal or ambiguous move '{}'", san))?;
+            board.apply_move(&chess_move);
+        }
+
+        let outcome = board.outcome();
+        if let (Some(result), Some(ChessOutcome::Checkmate { winner })) = (&result, outcome) {
+            let expected = if winner == Player::One { "1-0" } else { "0-1" };
+            if result != expected {
+                return Err(format!("result {} contradicts checkmate on the board", result));
+            }
+        }
+
+        Ok(board)
     }
 
     /// Update FEN notation from current board state
@@ -347,45 +1087,599 @@ impl ChessBoard {
             if empty_count > 0 {
                 fen.push_str(&empty_count.to_string());
             }
-            if rank < 7 {
-                fen.push('/');
+            if rank < 7 {
+                fen.push('/');
+            }
+        }
+
+        // Turn
+        fen.push(' ');
+        fen.push(if self.white_turn { 'w' } else { 'b' });
+
+        // Castling (X-FEN: rook file letters only where KQkq would be ambiguous)
+        fen.push(' ');
+        fen.push_str(&self.castling_field(false));
+
+        // En passant
+        fen.push(' ');
+        if self.en_passant >= 0 {
+            let ep_file = (self.en_passant % 8) as u8;
+            let ep_rank = 7 - (self.en_passant / 8) as u8;
+            fen.push((b'a' + ep_file) as char);
+            fen.push((b'1' + ep_rank) as char);
+        } else {
+            fen.push('-');
+        }
+
+        // Halfmove clock
+        fen.push(' ');
+        fen.push_str(&self.halfmove.to_string());
+
+        // Fullmove number
+        fen.push(' ');
+        fen.push_str(&self.fullmove.to_string());
+
+        self.fen = fen;
+    }
+
+    /// Current position in Shredder-FEN (castling rights always named by rook file)
+    pub fn shredder_fen(&self) -> String {
+        let mut fields: Vec<String> = self.fen.split(' ').map(|f| f.to_string()).collect();
+        if fields.len() > 2 {
+            fields[2] = self.castling_field(true);
+        }
+        fields.join(" ")
+    }
+
+    /// Castling field for FEN output: X-FEN uses KQkq for the outermost rook
+    /// on each side and a file letter otherwise, Shredder-FEN always uses letters
+    fn castling_field(&self, shredder: bool) -> String {
+        let mut field = String::new();
+        for right in 0..4 {
+            if !self.castling.get(right).copied().unwrap_or(false) {
+                continue;
+            }
+            let white = right < 2;
+            let kingside = right % 2 == 0;
+            let back_row = if white { 7 } else { 0 };
+            let rook = if white { 'R' } else { 'r' };
+            let file = self.rook_file(right);
+
+            let mut beyond = if kingside { file + 1..8 } else { 0..file };
+            let outermost = !beyond.any(|f| self.get_piece(back_row * 8 + f as usize) == rook);
+            let letter = if shredder || !outermost {
+                (b'a' + file) as char
+            } else if kingside {
+                'k'
+            } else {
+                'q'
+            };
+            field.push(if white { letter.to_ascii_uppercase() } else { letter });
+        }
+        if field.is_empty() {
+            field.push('-');
+        }
+        field
+    }
+}
+
+/// How a chess game ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChessOutcome {
+    /// The side to move is in check with no legal moves
+    Checkmate { winner: Player },
+    /// The side to move has no legal moves but is not in check
+    Stalemate,
+    /// Neither side has enough material to deliver mate
+    InsufficientMaterial,
+    /// Claimed after 50 moves by each side without a capture or pawn move
+    FiftyMoveRule,
+    /// Automatic after 75 moves by each side without a capture or pawn move
+    SeventyFiveMoveRule,
+    /// Claimed when the same position occurs for the third time
+    ThreefoldRepetition,
+    /// Automatic when the same position occurs for the fifth time
+    FivefoldRepetition,
+}
+
+impl ChessOutcome {
+    /// Winning player, None for drawn outcomes
+    pub fn winner(&self) -> Option<Player> {
+        match self {
+            ChessOutcome::Checkmate { winner } => Some(*winner),
+            _ => None,
+        }
+    }
+
+    /// Reason string stored in `GameRoom::end_reason`
+    pub fn reason(&self) -> &'static str {
+        match self {
+            ChessOutcome::Checkmate { .. } => "checkmate",
+            ChessOutcome::Stalemate => "stalemate",
+            ChessOutcome::InsufficientMaterial => "insufficient_material",
+            ChessOutcome::FiftyMoveRule => "fifty_move_rule",
+            ChessOutcome::SeventyFiveMoveRule => "seventy_five_move_rule",
+            ChessOutcome::ThreefoldRepetition => "threefold_repetition",
+            ChessOutcome::FivefoldRepetition => "fivefold_repetition",
+        }
+    }
+}
+
+/// A single chess move between two board indices (a8=0, h1=63)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChessMove {
+    pub from: u8,
+    pub to: u8,
+    /// Promotion piece in lowercase (q, r, b, n)
+    pub promotion: Option<char>,
+}
+
+impl ChessMove {
+    /// Parse strict UCI notation: two squares plus an optional promotion
+    /// piece in lowercase (q, r, b or n)
+    pub fn from_uci(uci: &str) -> Option<Self> {
+        if !uci.is_ascii() || (uci.len() != 4 && uci.len() != 5) {
+            return None;
+        }
+
+        let from = parse_square(&uci[0..2])?;
+        let to = parse_square(&uci[2..4])?;
+        if from == to {
+            return None;
+        }
+
+        let promotion = match uci.chars().nth(4) {
+            None => None,
+            Some(p @ ('q' | 'r' | 'b' | 'n')) => Some(p),
+            Some(_) => return None,
+        };
+
+        Some(Self { from, to, promotion })
+    }
+
+    /// Format the move in UCI notation (e.g., "e2e4", "e7e8q")
+    pub fn to_uci(&self) -> String {
+        let mut uci = square_name(self.from);
+        uci.push_str(&square_name(self.to));
+        if let Some(p) = self.promotion {
+            uci.push(p);
+        }
+        uci
+    }
+}
+
+/// Convert a board index (a8=0, h1=63) into a square name like "e4"
+pub fn square_name(idx: u8) -> String {
+    let file = (b'a' + idx % 8) as char;
+    let rank = (b'1' + 7 - idx / 8) as char;
+    format!("{}{}", file, rank)
+}
+
+/// Parse a square name like "e4" into a board index (a8=0, h1=63)
+pub fn parse_square(name: &str) -> Option<u8> {
+    let bytes = name.as_bytes();
+    if bytes.len() != 2 {
+        return None;
+    }
+    let file = bytes[0].wrapping_sub(b'a');
+    let rank = bytes[1].wrapping_sub(b'1');
+    if file > 7 || rank > 7 {
+        return None;
+    }
+    Some((7 - rank) * 8 + file)
+}
+
+const KNIGHT_OFFSETS: [(i32, i32); 8] = [
+    (-2, -1), (-2, 1), (-1, -2), (-1, 2),
+    (1, -2), (1, 2), (2, -1), (2, 1),
+];
+
+const KING_OFFSETS: [(i32, i32); 8] = [
+    (-1, -1), (-1, 0), (-1, 1),
+    (0, -1),           (0, 1),
+    (1, -1),  (1, 0),  (1, 1),
+];
+
+const ROOK_DIRECTIONS: [(i32, i32); 4] = [(-1, 0), (1, 0), (0, -1), (0, 1)];
+
+const BISHOP_DIRECTIONS: [(i32, i32); 4] = [(-1, -1), (-1, 1), (1, -1), (1, 1)];
+
+/// Classical rook files matching the castling rights order [K, Q, k, q]
+const CLASSICAL_ROOK_FILES: [u8; 4] = [7, 0, 7, 0];
+
+/// Knight file pairs for the last digit of a Chess960 Scharnagl number
+const CHESS960_KNIGHTS: [(usize, usize); 10] = [
+    (0, 1), (0, 2), (0, 3), (0, 4), (1, 2),
+    (1, 3), (1, 4), (2, 3), (2, 4), (3, 4),
+];
+
+/// Black back rank (a8..h8) for a Chess960 Scharnagl number (taken modulo 960)
+pub fn chess960_back_rank(index: u16) -> [char; 8] {
+    let mut n = (index % 960) as usize;
+    let mut rank = [' '; 8];
+
+    // Bishops on opposite colours
+    rank[(n % 4) * 2 + 1] = 'b';
+    n /= 4;
+    rank[(n % 4) * 2] = 'b';
+    n /= 4;
+
+    // Queen on one of the six remaining squares
+    let empty: Vec<usize> = (0..8).filter(|&f| rank[f] == ' ').collect();
+    rank[empty[n % 6]] = 'q';
+    n /= 6;
+
+    // Knights on two of the five remaining squares
+    let empty: Vec<usize> = (0..8).filter(|&f| rank[f] == ' ').collect();
+    let (first, second) = CHESS960_KNIGHTS[n];
+    rank[empty[first]] = 'n';
+    rank[empty[second]] = 'n';
+
+    // Rook, king, rook on the last three squares
+    let empty: Vec<usize> = (0..8).filter(|&f| rank[f] == ' ').collect();
+    rank[empty[0]] = 'r';
+    rank[empty[1]] = 'k';
+    rank[empty[2]] = 'r';
+    rank
+}
+
+/// Deterministic Chess960 Scharnagl number derived from room data
+pub fn chess960_index(seed: &str) -> u16 {
+    let digest = Sha256::digest(seed.as_bytes());
+    u16::from_be_bytes([digest[0], digest[1]]) % 960
+}
+
+/// Compact position used for move generation and legality checks
+#[derive(Debug, Clone, Copy)]
+struct ChessPosition {
+    squares: [char; 64],
+    white_turn: bool,
+    castling: [bool; 4],
+    /// Rook square for each castling right
+    castling_rooks: [usize; 4],
+    en_passant: i8,
+}
+
+impl ChessPosition {
+    /// Step from a square by (row, column) deltas, None if off the board
+    fn offset(idx: usize, dr: i32, dc: i32) -> Option<usize> {
+        let row = (idx / 8) as i32 + dr;
+        let col = (idx % 8) as i32 + dc;
+        if (0..8).contains(&row) && (0..8).contains(&col) {
+            Some((row * 8 + col) as usize)
+        } else {
+            None
+        }
+    }
+
+    /// Does `piece` belong to the given side?
+    fn is_own(piece: char, white: bool) -> bool {
+        piece != ' ' && piece.is_ascii_uppercase() == white
+    }
+
+    /// Is the square attacked by any piece of the given side?
+    fn is_attacked(&self, idx: usize, by_white: bool) -> bool {
+        let piece_of = |lower: char| if by_white { lower.to_ascii_uppercase() } else { lower };
+
+        // Pawns attack diagonally forward, so look one row behind the target
+        let pawn_dr = if by_white { 1 } else { -1 };
+        for dc in [-1, 1] {
+            if let Some(sq) = Self::offset(idx, pawn_dr, dc) {
+                if self.squares[sq] == piece_of('p') {
+                    return true;
+                }
+            }
+        }
+
+        for (dr, dc) in KNIGHT_OFFSETS {
+            if let Some(sq) = Self::offset(idx, dr, dc) {
+                if self.squares[sq] == piece_of('n') {
+                    return true;
+                }
+            }
+        }
+
+        for (dr, dc) in KING_OFFSETS {
+            if let Some(sq) = Self::offset(idx, dr, dc) {
+                if self.squares[sq] == piece_of('k') {
+                    return true;
+                }
+            }
+        }
+
+        let sliders = [
+            (ROOK_DIRECTIONS, piece_of('r')),
+            (BISHOP_DIRECTIONS, piece_of('b')),
+        ];
+        for (directions, slider) in sliders {
+            for (dr, dc) in directions {
+                let mut current = idx;
+                while let Some(sq) = Self::offset(current, dr, dc) {
+                    let piece = self.squares[sq];
+                    if piece != ' ' {
+                        if piece == slider || piece == piece_of('q') {
+                            return true;
+                        }
+                        break;
+                    }
+                    current = sq;
+                }
+            }
+        }
+
+        false
+    }
+
+    /// Locate the king of the given side
+    fn king_square(&self, white: bool) -> Option<usize> {
+        let king = if white { 'K' } else { 'k' };
+        self.squares.iter().position(|&p| p == king)
+    }
+
+    /// Is the given side's king attacked?
+    fn in_check(&self, white: bool) -> bool {
+        self.king_square(white)
+            .map(|k| self.is_attacked(k, !white))
+            .unwrap_or(false)
+    }
+
+    /// Does the move capture a piece (including en passant)?
+    fn is_capture(&self, chess_move: &ChessMove) -> bool {
+        let piece = self.squares[chess_move.from as usize];
+        let target = self.squares[chess_move.to as usize];
+        let white = piece.is_ascii_uppercase();
+        (target != ' ' && !Self::is_own(target, white))
+            || (piece.eq_ignore_ascii_case(&'p') && chess_move.from % 8 != chess_move.to % 8)
+    }
+
+    /// Is the move a castling move (encoded as the king capturing its own rook)?
+    fn is_castling(&self, chess_move: &ChessMove) -> bool {
+        let piece = self.squares[chess_move.from as usize];
+        let target = self.squares[chess_move.to as usize];
+        piece.eq_ignore_ascii_case(&'k')
+            && target.eq_ignore_ascii_case(&'r')
+            && Self::is_own(target, piece.is_ascii_uppercase())
+    }
+
+    /// Generate all legal moves for the side to move
+    fn legal_moves(&self) -> Vec<ChessMove> {
+        let mut moves = self.pseudo_legal_moves();
+        self.castling_moves(&mut moves);
+        moves.retain(|m| {
+            let mut next = *self;
+            next.apply(m);
+            !next.in_check(self.white_turn)
+        });
+        moves
+    }
+
+    /// Count leaf nodes of the legal move tree (see `ChessBoard::perft`)
+    fn perft(&self, depth: u32) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+        let moves = self.legal_moves();
+        if depth == 1 {
+            return moves.len() as u64;
+        }
+        moves
+            .iter()
+            .map(|m| {
+                let mut next = *self;
+                next.apply(m);
+                next.perft(depth - 1)
+            })
+            .sum()
+    }
+
+    /// Generate moves that follow piece movement rules, ignoring king safety
+    fn pseudo_legal_moves(&self) -> Vec<ChessMove> {
+        let white = self.white_turn;
+        let mut moves = Vec::new();
+
+        for from in 0..64usize {
+            let piece = self.squares[from];
+            if !Self::is_own(piece, white) {
+                continue;
+            }
+
+            match piece.to_ascii_lowercase() {
+                'p' => self.pawn_moves(from, &mut moves),
+                'n' => self.step_moves(from, &KNIGHT_OFFSETS, &mut moves),
+                'k' => self.step_moves(from, &KING_OFFSETS, &mut moves),
+                'b' => self.slide_moves(from, &BISHOP_DIRECTIONS, &mut moves),
+                'r' => self.slide_moves(from, &ROOK_DIRECTIONS, &mut moves),
+                'q' => {
+                    self.slide_moves(from, &ROOK_DIRECTIONS, &mut moves);
+                    self.slide_moves(from, &BISHOP_DIRECTIONS, &mut moves);
+                }
+                _ => {}
+            }
+        }
+
+        moves
+    }
+
+    fn pawn_moves(&self, from: usize, moves: &mut Vec<ChessMove>) {
+        let white = self.white_turn;
+        let dir = if white { -1 } else { 1 };
+        let start_row = if white { 6 } else { 1 };
+
+        if let Some(one) = Self::offset(from, dir, 0) {
+            if self.squares[one] == ' ' {
+                Self::push_pawn_move(from, one, moves);
+                if from / 8 == start_row {
+                    if let Some(two) = Self::offset(from, 2 * dir, 0) {
+                        if self.squares[two] == ' ' {
+                            Self::push_pawn_move(from, two, moves);
+                        }
+                    }
+                }
+            }
+        }
+
+        for dc in [-1, 1] {
+            if let Some(target) = Self::offset(from, dir, dc) {
+                let occupant = self.squares[target];
+                let is_enemy = occupant != ' ' && !Self::is_own(occupant, white);
+                if is_enemy || target as i8 == self.en_passant {
+                    Self::push_pawn_move(from, target, moves);
+                }
+            }
+        }
+    }
+
+    /// Push a pawn move, expanding it into the four promotions on the last rank
+    fn push_pawn_move(from: usize, to: usize, moves: &mut Vec<ChessMove>) {
+        let (from, to) = (from as u8, to as u8);
+        if to / 8 == 0 || to / 8 == 7 {
+            for promotion in ['q', 'r', 'b', 'n'] {
+                moves.push(ChessMove { from, to, promotion: Some(promotion) });
+            }
+        } else {
+            moves.push(ChessMove { from, to, promotion: None });
+        }
+    }
+
+    fn step_moves(&self, from: usize, offsets: &[(i32, i32)], moves: &mut Vec<ChessMove>) {
+        for &(dr, dc) in offsets {
+            if let Some(to) = Self::offset(from, dr, dc) {
+                if !Self::is_own(self.squares[to], self.white_turn) {
+                    moves.push(ChessMove { from: from as u8, to: to as u8, promotion: None });
+                }
+            }
+        }
+    }
+
+    fn slide_moves(&self, from: usize, directions: &[(i32, i32)], moves: &mut Vec<ChessMove>) {
+        for &(dr, dc) in directions {
+            let mut current = from;
+            while let Some(to) = Self::offset(current, dr, dc) {
+                let occupant = self.squares[to];
+                if Self::is_own(occupant, self.white_turn) {
+                    break;
+                }
+                moves.push(ChessMove { from: from as u8, to: to as u8, promotion: None });
+                if occupant != ' ' {
+                    break;
+                }
+                current = to;
+            }
+        }
+    }
+
+    /// Castling: rights held, king and rook in place, path clear and not attacked
+    ///
+    /// Castling moves are encoded as the king moving onto its own rook, which
+    /// works for both classical chess and Chess960.
+    fn castling_moves(&self, moves: &mut Vec<ChessMove>) {
+        let white = self.white_turn;
+        let (rook, rights, back_row) = if white {
+            ('R', [0, 1], 7)
+        } else {
+            ('r', [2, 3], 0)
+        };
+
+        let king_from = match self.king_square(white) {
+            Some(k) if k / 8 == back_row => k,
+            _ => return,
+        };
+        if self.in_check(white) {
+            return;
+        }
+
+        for right in rights {
+            if !self.castling[right] {
+                continue;
+            }
+            let rook_from = self.castling_rooks[right];
+            if self.squares[rook_from] != rook {
+                continue;
+            }
+
+            let kingside = right % 2 == 0;
+            let king_to = back_row * 8 + if kingside { 6 } else { 2 };
+            let rook_to = back_row * 8 + if kingside { 5 } else { 3 };
+
+            // Every square either piece crosses must be empty (apart from the two castling pieces)
+            let low = king_from.min(king_to).min(rook_from).min(rook_to);
+            let high = king_from.max(king_to).max(rook_from).max(rook_to);
+            let path_clear = (low..=high)
+                .all(|sq| sq == king_from || sq == rook_from || self.squares[sq] == ' ');
+            if !path_clear {
+                continue;
+            }
+
+            // The king may not pass through or land on an attacked square
+            let (step_low, step_high) = (king_from.min(king_to), king_from.max(king_to));
+            if (step_low..=step_high).any(|sq| self.is_attacked(sq, !white)) {
+                continue;
             }
+
+            moves.push(ChessMove { from: king_from as u8, to: rook_from as u8, promotion: None });
         }
+    }
 
-        // Turn
-        fen.push(' ');
-        fen.push(if self.white_turn { 'w' } else { 'b' });
+    /// Apply a move without any legality checks
+    fn apply(&mut self, chess_move: &ChessMove) {
+        let from = chess_move.from as usize;
+        let to = chess_move.to as usize;
+        let piece = self.squares[from];
+        let white = piece.is_ascii_uppercase();
+        let piece_lower = piece.to_ascii_lowercase();
 
-        // Castling
-        fen.push(' ');
-        let mut castling_str = String::new();
-        if self.castling.get(0).copied().unwrap_or(false) { castling_str.push('K'); }
-        if self.castling.get(1).copied().unwrap_or(false) { castling_str.push('Q'); }
-        if self.castling.get(2).copied().unwrap_or(false) { castling_str.push('k'); }
-        if self.castling.get(3).copied().unwrap_or(false) { castling_str.push('q'); }
-        if castling_str.is_empty() { castling_str.push('-'); }
-        fen.push_str(&castling_str);
+        // En passant capture removes the pawn behind the target square
+        if piece_lower == 'p' && from % 8 != to % 8 && self.squares[to] == ' ' {
+            let captured_idx = if white { to + 8 } else { to - 8 };
+            self.squares[captured_idx] = ' ';
+        }
 
-        // En passant
-        fen.push(' ');
-        if self.en_passant >= 0 {
-            let ep_file = (self.en_passant % 8) as u8;
-            let ep_rank = 7 - (self.en_passant / 8) as u8;
-            fen.push((b'a' + ep_file) as char);
-            fen.push((b'1' + ep_rank) as char);
-        } else {
-            fen.push('-');
+        // Castling sends the king to the g/c file and the rook to the f/d file
+        let mut castle_to = None;
+        if self.is_castling(chess_move) {
+            let back_row = from / 8;
+            let kingside = to > from;
+            let king_to = back_row * 8 + if kingside { 6 } else { 2 };
+            let rook_to = back_row * 8 + if kingside { 5 } else { 3 };
+            castle_to = Some((king_to, rook_to));
         }
 
-        // Halfmove clock
-        fen.push(' ');
-        fen.push_str(&self.halfmove.to_string());
+        // Update castling rights: king moves lose both, rook moves or captures lose one
+        if piece_lower == 'k' {
+            let rights = if white { [0, 1] } else { [2, 3] };
+            for right in rights {
+                self.castling[right] = false;
+            }
+        }
+        for (right, &sq) in self.castling_rooks.iter().enumerate() {
+            if from == sq || to == sq {
+                self.castling[right] = false;
+            }
+        }
 
-        // Fullmove number
-        fen.push(' ');
-        fen.push_str(&self.fullmove.to_string());
+        // Set en passant square for next move
+        self.en_passant = -1;
+        if piece_lower == 'p' && (to / 8).abs_diff(from / 8) == 2 {
+            self.en_passant = ((from + to) / 2) as i8;
+        }
 
-        self.fen = fen;
+        // Move the pieces (clear both origins first so rook and king can swap squares)
+        let moved = match chess_move.promotion {
+            Some(p) if white => p.to_ascii_uppercase(),
+            Some(p) => p.to_ascii_lowercase(),
+            None => piece,
+        };
+        self.squares[from] = ' ';
+        if let Some((king_to, rook_to)) = castle_to {
+            let rook = self.squares[to];
+            self.squares[to] = ' ';
+            self.squares[rook_to] = rook;
+            self.squares[king_to] = moved;
+        } else {
+            self.squares[to] = moved;
+        }
+
+        self.white_turn = !self.white_turn;
     }
 }
 
@@ -758,14 +2052,19 @@ impl GomokuBoard {
 // ============================================================================
 
 /// Battleship game state (10x10 grid per player)
+///
+/// Fleets stay hidden: each player only publishes a salted SHA-256
+/// commitment of their placement, answers the opponent's shots, and reveals
+/// the placement at the end so every answer can be checked.
 #[derive(Debug, Clone, Serialize, Deserialize, SimpleObject, InputObject, Default)]
 #[graphql(input_name = "BattleshipBoardInput")]
 pub struct BattleshipBoard {
     /// Player 1's board (ship positions): 0=water, 1-5=ship id
+    /// Empty until the fleet is revealed
     pub p1_ships: Vec<u8>,
     /// Player 1's hits received: 0=unknown, 1=miss, 2=hit
     pub p1_hits: Vec<u8>,
-    /// Player 2's board (ship positions)
+    /// Player 2's board (ship positions), empty until revealed
     pub p2_ships: Vec<u8>,
     /// Player 2's hits received
     pub p2_hits: Vec<u8>,
@@ -775,60 +2074,83 @@ pub struct BattleshipBoard {
     pub p1_ready: bool,
     /// Player 2 ready (placed ships)
     pub p2_ready: bool,
-    /// Move history (attack positions)
+    /// Move history (attack positions); Player One fires the even entries
     pub moves: Vec<u8>,
+    /// Defender's answer for each attack in `moves`: 1=miss, 2=hit, 3=sunk
+    pub answers: Vec<u8>,
     /// Ships sunk count [p1, p2]
     pub ships_sunk: Vec<u8>,
+    /// Fleet commitments [p1, p2]: hex SHA-256 of "salt:placement"
+    pub commitments: Vec<String>,
+    /// Attack waiting for the defender's answer
+    pub pending_attack: Option<u8>,
+    /// Reveal phase: both players must open their commitments
+    pub reveal_phase: bool,
+    /// Fleet revealed [p1, p2]
+    pub revealed: Vec<bool>,
+    /// Reveal matched the commitment and every answer [p1, p2]
+    pub honest: Vec<bool>,
 }
 
+/// Ship sizes by id (1=carrier, 2=battleship, 3=cruiser, 4=submarine, 5=destroyer)
+const BATTLESHIP_SHIP_SIZES: [u8; 5] = [5, 4, 3, 3, 2];
+
+/// Answer codes stored in `BattleshipBoard::answers`
+const BATTLESHIP_MISS: u8 = 1;
+const BATTLESHIP_HIT: u8 = 2;
+const BATTLESHIP_SUNK: u8 = 3;
+
 impl BattleshipBoard {
     pub fn new() -> Self {
         Self {
-            p1_ships: vec![0u8; 100],
+            p1_ships: Vec::new(),
             p1_hits: vec![0u8; 100],
-            p2_ships: vec![0u8; 100],
+            p2_ships: Vec::new(),
             p2_hits: vec![0u8; 100],
             setup_phase: true,
             p1_ready: false,
             p2_ready: false,
             moves: Vec::new(),
+            answers: Vec::new(),
             ships_sunk: vec![0, 0],
+            commitments: vec![String::new(), String::new()],
+            pending_attack: None,
+            reveal_phase: false,
+            revealed: vec![false, false],
+            honest: vec![false, false],
         }
     }
 
-    /// Place ships for a player (ship_data format: "ship_id,start_pos,horizontal;...")
+    /// Parse a fleet placement (format: "ship_id,start_pos,horizontal;...")
     /// Ships: 1=carrier(5), 2=battleship(4), 3=cruiser(3), 4=submarine(3), 5=destroyer(2)
-    pub fn place_ships(&mut self, player: Player, ship_data: &str) -> bool {
-        let ships = if player == Player::One { &mut self.p1_ships } else { &mut self.p2_ships };
-        
-        // Reset ships
-        for cell in ships.iter_mut() {
-            *cell = 0;
-        }
+    /// All five ships must be placed exactly once.
+    pub fn parse_fleet(ship_data: &str) -> Option<Vec<u8>> {
+        let mut ships = vec![0u8; 100];
+        let mut placed = [false; 5];
 
-        let ship_sizes: [u8; 5] = [5, 4, 3, 3, 2];
-        
         for ship_str in ship_data.split(';') {
             let parts: Vec<&str> = ship_str.split(',').collect();
-            if parts.len() != 3 { return false; }
-            
+            if parts.len() != 3 { return None; }
+
             let ship_id: u8 = parts[0].parse().unwrap_or(0);
             let start_pos: u8 = parts[1].parse().unwrap_or(100);
             let horizontal: bool = parts[2] == "h";
-            
-            if ship_id < 1 || ship_id > 5 || start_pos >= 100 { return false; }
-            
-            let size = ship_sizes[(ship_id - 1) as usize];
+
+            if !(1..=5).contains(&ship_id) || start_pos >= 100 { return None; }
+            if placed[(ship_id - 1) as usize] { return None; }
+            placed[(ship_id - 1) as usize] = true;
+
+            let size = BATTLESHIP_SHIP_SIZES[(ship_id - 1) as usize];
             let start_row = start_pos / 10;
             let start_col = start_pos % 10;
-            
+
             // Check bounds
             if horizontal {
-                if start_col + size > 10 { return false; }
-            } else {
-                if start_row + size > 10 { return false; }
+                if start_col + size > 10 { return None; }
+            } else if start_row + size > 10 {
+                return None;
             }
-            
+
             // Check overlap and place
             for i in 0..size {
                 let pos = if horizontal {
@@ -836,12 +2158,32 @@ impl BattleshipBoard {
                 } else {
                     start_pos + i * 10
                 };
-                
-                if ships[pos as usize] != 0 { return false; } // Overlap
+
+                if ships[pos as usize] != 0 { return None; } // Overlap
                 ships[pos as usize] = ship_id;
             }
         }
 
+        if placed.iter().all(|&p| p) { Some(ships) } else { None }
+    }
+
+    /// Salted commitment to a fleet placement (hex SHA-256 of "salt:placement")
+    pub fn fleet_commitment(salt: &str, ship_data: &str) -> String {
+        hex::encode(Sha256::digest(format!("{}:{}", salt, ship_data).as_bytes()))
+    }
+
+    /// Record a player's fleet commitment; Player One commits first
+    pub fn commit_fleet(&mut self, player: Player, commitment: &str) -> bool {
+        let is_hash = commitment.len() == 64 && commitment.chars().all(|c| c.is_ascii_hexdigit());
+        if !self.setup_phase || !is_hash || !self.commitments[player.index()].is_empty() {
+            return false;
+        }
+        if player == Player::Two && self.commitments[0].is_empty() {
+            return false;
+        }
+
+        self.commitments[player.index()] = commitment.to_ascii_lowercase();
+
         // Mark player as ready
         if player == Player::One {
             self.p1_ready = true;
@@ -857,56 +2199,142 @@ impl BattleshipBoard {
         true
     }
 
-    /// Attack a position, returns (hit, sunk_ship_id)
-    pub fn attack(&mut self, attacker: Player, pos: u8) -> (bool, u8) {
-        if pos >= 100 || self.setup_phase { return (false, 0); }
-
-        let (target_ships, target_hits) = if attacker == Player::One {
-            (&self.p2_ships, &mut self.p2_hits)
-        } else {
-            (&self.p1_ships, &mut self.p1_hits)
-        };
+    /// Fire at a position; the defender must answer before play continues
+    pub fn attack(&mut self, attacker: Player, pos: u8) -> bool {
+        if pos >= 100 || self.setup_phase || self.reveal_phase || self.pending_attack.is_some() {
+            return false;
+        }
 
         // Already attacked this position
-        if target_hits[pos as usize] != 0 {
-            return (false, 0);
+        if self.hits_received(attacker.other())[pos as usize] != 0 {
+            return false;
         }
 
         self.moves.push(pos);
+        self.pending_attack = Some(pos);
+        true
+    }
 
-        if target_ships[pos as usize] != 0 {
-            // Hit!
-            target_hits[pos as usize] = 2;
-            let ship_id = target_ships[pos as usize];
-            
-            // Check if ship is sunk
-            let mut sunk = true;
-            for i in 0..100 {
-                if target_ships[i] == ship_id && target_hits[i] != 2 {
-                    sunk = false;
-                    break;
-                }
+    /// Defender answers the pending attack with "miss", "hit" or "sunk"
+    pub fn answer(&mut self, defender: Player, pos: u8, result: &str) -> bool {
+        if self.pending_attack != Some(pos) {
+            return false;
+        }
+
+        let code = match result {
+            "miss" => BATTLESHIP_MISS,
+            "hit" => BATTLESHIP_HIT,
+            "sunk" => BATTLESHIP_SUNK,
+            _ => return false,
+        };
+
+        let hits = if defender == Player::One { &mut self.p1_hits } else { &mut self.p2_hits };
+        hits[pos as usize] = if code == BATTLESHIP_MISS { 1 } else { 2 };
+        if code == BATTLESHIP_SUNK {
+            self.ships_sunk[defender.index()] += 1;
+        }
+        self.answers.push(code);
+        self.pending_attack = None;
+
+        // The defender fires next; stop once a fleet is gone or they have
+        // nothing left to shoot at
+        let fleet_sunk = self.ships_sunk[defender.index()] >= 5;
+        let no_targets = self.hits_received(defender.other()).iter().all(|&h| h != 0);
+        if fleet_sunk || no_targets {
+            self.reveal_phase = true;
+        }
+
+        true
+    }
+
+    /// Answer a shot against a fleet, given the shots already taken at it
+    pub fn answer_for(ships: &[u8], hits: &[u8], pos: u8) -> &'static str {
+        let ship_id = ships[pos as usize];
+        if ship_id == 0 {
+            return "miss";
+        }
+
+        let sunk = (0..100).all(|i| ships[i] != ship_id || i == pos as usize || hits[i] == 2);
+        if sunk { "sunk" } else { "hit" }
+    }
+
+    /// Open a player's commitment ("salt:placement") and check every answer
+    /// they gave against the revealed fleet
+    pub fn reveal(&mut self, player: Player, opening: &str) -> bool {
+        if !self.reveal_phase || self.revealed[player.index()] {
+            return false;
+        }
+
+        self.revealed[player.index()] = true;
+
+        let fleet = opening.split_once(':').and_then(|(salt, ship_data)| {
+            let matches = Self::fleet_commitment(salt, ship_data) == self.commitments[player.index()];
+            if matches { Self::parse_fleet(ship_data) } else { None }
+        });
+
+        if let Some(ships) = fleet {
+            self.honest[player.index()] = self.answers_match(player, &ships);
+            if player == Player::One {
+                self.p1_ships = ships;
+            } else {
+                self.p2_ships = ships;
             }
-            
-            if sunk {
-                let sunk_idx = if attacker == Player::One { 1 } else { 0 };
-                self.ships_sunk[sunk_idx] += 1;
-                return (true, ship_id);
+        }
+
+        true
+    }
+
+    /// Replay the shots taken at `defender` and compare with their answers
+    fn answers_match(&self, defender: Player, ships: &[u8]) -> bool {
+        // Player One fires the even-numbered shots
+        let first_shot = if defender == Player::One { 1 } else { 0 };
+        let mut hits = vec![0u8; 100];
+
+        for (i, &pos) in self.moves.iter().enumerate().skip(first_shot).step_by(2) {
+            let expected = Self::answer_for(ships, &hits, pos);
+            let given = match self.answers.get(i) {
+                Some(&BATTLESHIP_MISS) => "miss",
+                Some(&BATTLESHIP_HIT) => "hit",
+                Some(&BATTLESHIP_SUNK) => "sunk",
+                _ => return false,
+            };
+            if expected != given {
+                return false;
             }
-            
-            (true, 0)
-        } else {
-            // Miss
-            target_hits[pos as usize] = 1;
-            (false, 0)
+            hits[pos as usize] = if ships[pos as usize] == 0 { 1 } else { 2 };
         }
+
+        true
     }
 
-    /// Check if game is over (all ships sunk)
-    pub fn check_winner(&self) -> Option<Player> {
-        if self.ships_sunk[0] >= 5 { return Some(Player::Two); } // P1's ships all sunk
-        if self.ships_sunk[1] >= 5 { return Some(Player::One); } // P2's ships all sunk
-        None
+    /// Shots received by a player
+    fn hits_received(&self, player: Player) -> &[u8] {
+        if player == Player::One { &self.p1_hits } else { &self.p2_hits }
+    }
+
+    /// Final result once both fleets are revealed: (winner, reason)
+    ///
+    /// A player whose reveal does not match their commitment or answers
+    /// forfeits; otherwise whoever sank the whole enemy fleet wins.
+    pub fn result(&self) -> Option<(Option<Player>, &'static str)> {
+        if !self.revealed.iter().all(|&r| r) {
+            return None;
+        }
+
+        Some(match (self.honest[0], self.honest[1]) {
+            (false, false) => (None, "false_answers"),
+            (false, true) => (Some(Player::Two), "false_answers"),
+            (true, false) => (Some(Player::One), "false_answers"),
+            (true, true) => {
+                if self.ships_sunk[0] >= 5 {
+                    (Some(Player::Two), "fleet_sunk") // P1's ships all sunk
+                } else if self.ships_sunk[1] >= 5 {
+                    (Some(Player::One), "fleet_sunk") // P2's ships all sunk
+                } else {
+                    (None, "no_targets")
+                }
+            }
+        })
     }
 }
 
@@ -1033,7 +2461,7 @@ impl MancalaBoard {
 // ============================================================================
 
 /// Main game room state
-#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
 pub struct GameRoom {
     // === Identity ===
     /// Host chain ID (room code)
@@ -1044,9 +2472,13 @@ pub struct GameRoom {
     pub player_wallets: Vec<String>,
     /// Player usernames [host, joiner]
     pub usernames: Vec<String>,
+    /// Hub chain that arbitrates disputes for this room
+    pub hub_chain_id: String,
 
     // === Game Type ===
     pub game_type: GameType,
+    /// Unrated rooms allow takebacks (rooms are rated unless created otherwise)
+    pub rated: bool,
 
     // === Game Boards (only one will be Some based on game_type) ===
     pub chess_board: Option<ChessBoard>,
@@ -1061,10 +2493,149 @@ pub struct GameRoom {
     pub current_turn: Player,
     pub winner: Option<Player>,
     pub end_reason: Option<String>,
+    /// Player with a pending draw offer (expires when they move again)
+    pub draw_offer: Option<Player>,
+    /// Pending takeback request (expires on the next move)
+    pub takeback_request: Option<TakebackRequest>,
 
     // === Timestamps ===
     pub created_at: u64,
+    /// When the second player joined and the game (and its clocks) started
+    pub started_at: u64,
     pub last_move_at: u64,
+
+    // === Clocks ===
+    /// Time control chosen at creation (None for untimed rooms)
+    pub time_control: Option<TimeControl>,
+    /// Clock time left per player in microseconds, as of `last_move_at`
+    pub clocks: Vec<u64>,
+    /// Vacation days each player has taken in this room (correspondence)
+    pub vacation_days: Vec<u64>,
+
+    // === History ===
+    /// Number of moves applied so far (drops only when a takeback rewinds the log)
+    pub sequence: u64,
+    /// Every accepted move, in order (replayed by the opponent's chain)
+    pub move_log: Vec<MoveRecord>,
+    /// Head of the room's hash chain (empty before the first move)
+    pub state_hash: String,
+}
+
+/// A player's clock as seen at query time
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct PlayerDeadline {
+    pub player: Player,
+    pub username: String,
+    /// Clock time left in microseconds
+    pub time_left: u64,
+    /// When the clock runs out (only for the player to move)
+    pub deadline: Option<u64>,
+    /// Vacation days taken in this room
+    pub vacation_days: u64,
+}
+
+/// A request to undo the last `plies` moves, valid while the room is
+/// still at `sequence`
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct TakebackRequest {
+    pub player: Player,
+    pub plies: u64,
+    pub sequence: u64,
+}
+
+/// A single accepted move, as exchanged between the two player chains
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct MoveRecord {
+    /// Position in the room's move log, starting at 1
+    pub sequence: u64,
+    pub player: Player,
+    pub move_data: MoveData,
+    pub timestamp: u64,
+    /// Room hash after this move (see `GameRoom::chain_hash`)
+    pub state_hash: String,
+}
+
+/// Reward paid to a player for a room
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, SimpleObject)]
+pub struct RewardPayout {
+    pub xp: u64,
+    pub coins: u64,
+}
+
+/// Binding ruling of the hub chain on a disputed room
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct DisputeVerdict {
+    pub room_id: String,
+    /// Ruled status (InProgress if the game is undecided)
+    pub status: GameStatus,
+    pub winner: Option<Player>,
+    /// Why the game ended, or None if the accepted moves leave it undecided
+    pub end_reason: Option<String>,
+    /// Moves the hub accepted, in order
+    pub move_log: Vec<MoveRecord>,
+    pub state_hash: String,
+    pub decided_at: u64,
+}
+
+/// A move whose replay did not reproduce the sender's state hash; it is
+/// left unapplied and the room goes to the hub for a ruling
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct RoomDivergence {
+    pub room_id: String,
+    /// Sequence number of the first move that diverged
+    pub sequence: u64,
+    /// Our hash after that move (or before it, if it was illegal here)
+    pub local_hash: String,
+    /// Hash the opponent's chain reported
+    pub remote_hash: String,
+    pub detected_at: u64,
+}
+
+/// Integrity summary of the current room
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct RoomIntegrity {
+    pub room_id: String,
+    pub moves: u64,
+    pub state_hash: String,
+    /// False once the opponent's chain reported a different state
+    pub in_sync: bool,
+    pub divergence: Option<RoomDivergence>,
+}
+
+impl Default for GameRoom {
+    /// An empty room, rated like every room made by `GameRoom::new`
+    fn default() -> Self {
+        Self {
+            host_chain_id: String::new(),
+            player_chain_ids: Vec::new(),
+            player_wallets: Vec::new(),
+            usernames: Vec::new(),
+            hub_chain_id: String::new(),
+            game_type: GameType::default(),
+            rated: true,
+            chess_board: None,
+            connect_four_board: None,
+            reversi_board: None,
+            gomoku_board: None,
+            battleship_board: None,
+            mancala_board: None,
+            status: GameStatus::default(),
+            current_turn: Player::default(),
+            winner: None,
+            end_reason: None,
+            draw_offer: None,
+            takeback_request: None,
+            created_at: 0,
+            started_at: 0,
+            last_move_at: 0,
+            time_control: None,
+            clocks: Vec::new(),
+            vacation_days: Vec::new(),
+            sequence: 0,
+            move_log: Vec::new(),
+            state_hash: String::new(),
+        }
+    }
 }
 
 impl GameRoom {
@@ -1081,7 +2652,9 @@ impl GameRoom {
             player_chain_ids: vec![host_chain_id.to_string()],
             player_wallets: vec![format!("{:?}", host_wallet)],
             usernames: vec![host_username],
+            hub_chain_id: String::new(),
             game_type,
+            rated: true,
             chess_board: None,
             connect_four_board: None,
             reversi_board: None,
@@ -1092,8 +2665,17 @@ impl GameRoom {
             current_turn: Player::One,
             winner: None,
             end_reason: None,
+            draw_offer: None,
+            takeback_request: None,
             created_at: created_at.micros(),
+            started_at: 0,
             last_move_at: 0,
+            time_control: None,
+            clocks: Vec::new(),
+            vacation_days: Vec::new(),
+            sequence: 0,
+            move_log: Vec::new(),
+            state_hash: String::new(),
         };
 
         // Initialize the appropriate board
@@ -1109,6 +2691,173 @@ impl GameRoom {
         room
     }
 
+    /// Unique room identifier: host chain plus creation time
+    pub fn room_id(&self) -> String {
+        format!("{}:{}", self.host_chain_id, self.created_at)
+    }
+
+    /// Has the game reached a final result?
+    pub fn is_finished(&self) -> bool {
+        matches!(
+            self.status,
+            GameStatus::Finished | GameStatus::Draw | GameStatus::Forfeited
+        )
+    }
+
+    /// Nothing left to play: finished, or abandoned by the opponent
+    pub fn is_over(&self) -> bool {
+        self.is_finished() || self.status == GameStatus::Abandoned
+    }
+
+    /// The room as it was when the game started, before any move
+    pub fn initial_state(&self) -> GameRoom {
+        let mut room = GameRoom {
+            chess_board: None,
+            connect_four_board: None,
+            reversi_board: None,
+            gomoku_board: None,
+            battleship_board: None,
+            mancala_board: None,
+            status: GameStatus::InProgress,
+            current_turn: Player::One,
+            winner: None,
+            end_reason: None,
+            draw_offer: None,
+            takeback_request: None,
+            last_move_at: self.started_at,
+            clocks: self.starting_clocks(),
+            vacation_days: Vec::new(),
+            sequence: 0,
+            move_log: Vec::new(),
+            state_hash: String::new(),
+            ..self.clone()
+        };
+
+        match self.game_type {
+            GameType::Chess => {
+                let board = self
+                    .chess_board
+                    .as_ref()
+                    .map_or_else(ChessBoard::new, ChessBoard::initial_board);
+                if !board.white_turn {
+                    room.current_turn = Player::Two;
+                }
+                room.chess_board = Some(board);
+            }
+            GameType::ConnectFour => room.connect_four_board = Some(ConnectFourBoard::new()),
+            GameType::Reversi => room.reversi_board = Some(ReversiBoard::new()),
+            GameType::Gomoku => room.gomoku_board = Some(GomokuBoard::new()),
+            GameType::Battleship => room.battleship_board = Some(BattleshipBoard::new()),
+            GameType::Mancala => room.mancala_board = Some(MancalaBoard::new()),
+        }
+
+        room
+    }
+
+    /// Full clocks for both players (empty for untimed rooms)
+    pub fn starting_clocks(&self) -> Vec<u64> {
+        self.time_control
+            .as_ref()
+            .map_or_else(Vec::new, |t| vec![t.base_micros(); 2])
+    }
+
+    /// Clock time `player` has left at `now` (None for untimed rooms);
+    /// only the player to move has a running clock
+    pub fn time_left(&self, player: Player, now: u64) -> Option<u64> {
+        let control = self.time_control.as_ref()?;
+        let left = self.clocks.get(player.index()).copied().unwrap_or(0);
+        if self.status != GameStatus::InProgress || self.current_turn != player {
+            return Some(left);
+        }
+        let elapsed = now.saturating_sub(self.last_move_at);
+        Some(left.saturating_sub(control.charge(elapsed)))
+    }
+
+    /// Player who lost on time if `claimer` claims a timeout at `now`:
+    /// the opponent, if they are to move and their clock has run out
+    pub fn timeout_loser(&self, claimer: Player, now: u64) -> Option<Player> {
+        let loser = claimer.other();
+        if self.current_turn == loser && self.time_left(loser, now) == Some(0) {
+            Some(loser)
+        } else {
+            None
+        }
+    }
+
+    /// Chain of the other seat, seen from `chain_id`
+    pub fn other_chain(&self, chain_id: &str) -> Option<&String> {
+        let index = self.player_chain_ids.iter().position(|c| c == chain_id)?;
+        self.player_chain_ids.get(1 - index)
+    }
+
+    /// End the game at `now` with `loser` forfeiting (conceding, or out of
+    /// time) and return the chain `my_chain` must send MatchEnded to: the
+    /// winner's when we concede, the loser's when we claim a timeout
+    pub fn forfeit(
+        &mut self,
+        loser: Player,
+        reason: &str,
+        now: u64,
+        my_chain: &str,
+    ) -> Option<ChainId> {
+        self.status = GameStatus::Forfeited;
+        self.winner = Some(loser.other());
+        self.end_reason = Some(reason.to_string());
+        self.last_move_at = now;
+        self.other_chain(my_chain).and_then(|c| c.parse().ok())
+    }
+
+    /// When the clock of `player` runs out, if it is running
+    pub fn deadline(&self, player: Player) -> Option<u64> {
+        let control = self.time_control.as_ref()?;
+        if self.status != GameStatus::InProgress || self.current_turn != player {
+            return None;
+        }
+        let left = self.clocks.get(player.index()).copied().unwrap_or(0);
+        Some(
+            self.last_move_at
+                .saturating_add(control.grace_micros())
+                .saturating_add(left),
+        )
+    }
+
+    /// Vacation days `player` has taken in this room
+    pub fn vacation_taken(&self, player: Player) -> u64 {
+        self.vacation_days.get(player.index()).copied().unwrap_or(0)
+    }
+
+    /// Extend the running clock of `player` by `days` of vacation
+    pub fn take_vacation(&mut self, player: Player, days: u64) {
+        self.vacation_days.resize(2, 0);
+        let taken = &mut self.vacation_days[player.index()];
+        *taken = taken.saturating_add(days);
+        let clock = &mut self.clocks[player.index()];
+        *clock = clock.saturating_add(days.saturating_mul(TimeControl::DAY_MICROS));
+    }
+
+    /// Next link of the hash chain: SHA-256 over (previous hash, move,
+    /// resulting board), to be called after `record` has been applied
+    pub fn chain_hash(&self, record: &MoveRecord) -> String {
+        let played = (record.sequence, record.player, &record.move_data, record.timestamp);
+        let board = (
+            &self.chess_board,
+            &self.connect_four_board,
+            &self.reversi_board,
+            &self.gomoku_board,
+            &self.battleship_board,
+            &self.mancala_board,
+            self.status,
+            self.current_turn,
+            self.winner,
+        );
+
+        let mut hasher = Sha256::new();
+        hasher.update(self.state_hash.as_bytes());
+        hasher.update(bcs::to_bytes(&played).unwrap_or_default());
+        hasher.update(bcs::to_bytes(&board).unwrap_or_default());
+        hex::encode(hasher.finalize())
+    }
+
     /// Add joiner as player two
     pub fn add_joiner(
         &mut self,
@@ -1121,8 +2870,54 @@ impl GameRoom {
         self.player_wallets.push(joiner_wallet);
         self.usernames.push(joiner_username);
         self.status = GameStatus::InProgress;
+        self.started_at = now.micros();
         self.last_move_at = now.micros();
     }
+
+    /// Export the chess game as PGN (seven-tag roster plus SAN movetext)
+    pub fn chess_pgn(&self) -> Option<String> {
+        let board = self.chess_board.as_ref()?;
+        let result = self.pgn_result();
+        let player_name = |i: usize| {
+            self.usernames.get(i).cloned().unwrap_or_else(|| "?".to_string())
+        };
+        let tags = [
+            ("Event", "ChainCycles Chess".to_string()),
+            ("Site", format!("Linera chain {}", self.host_chain_id)),
+            ("Date", pgn_date(self.created_at)),
+            ("Round", "-".to_string()),
+            ("White", player_name(0)),
+            ("Black", player_name(1)),
+            ("Result", result.to_string()),
+        ];
+        Some(board.to_pgn(&tags, result))
+    }
+
+    /// PGN result token for the room's current status
+    pub fn pgn_result(&self) -> &'static str {
+        match (self.status, self.winner) {
+            (GameStatus::Finished | GameStatus::Forfeited, Some(Player::One)) => "1-0",
+            (GameStatus::Finished | GameStatus::Forfeited, Some(Player::Two)) => "0-1",
+            (GameStatus::Draw, _) => "1/2-1/2",
+            _ => "*",
+        }
+    }
+}
+
+/// Format a timestamp in microseconds as a PGN date ("YYYY.MM.DD")
+pub fn pgn_date(micros: u64) -> String {
+    // Civil-from-days conversion (proleptic Gregorian calendar)
+    let days = (micros / 86_400_000_000) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    format!("{:04}.{:02}.{:02}", year, month, day)
 }
 
 /// Player profile stored per-chain
@@ -1137,6 +2932,27 @@ pub struct PlayerProfile {
     pub xp: u64,
     pub coins: u64,
     pub created_at: u64,
+    /// Vacation days left for correspondence games
+    pub vacation_days: u64,
+    /// When the vacation bank was last refilled
+    pub vacation_refilled_at: u64,
+}
+
+impl PlayerProfile {
+    /// Vacation days a new profile starts with, the bank after each yearly
+    /// refill, and the most a player may take in one room
+    pub const VACATION_DAYS: u64 = 30;
+    /// How long after a refill the vacation bank fills up again
+    pub const VACATION_REFILL_MICROS: u64 = 365 * TimeControl::DAY_MICROS;
+
+    /// Refill the vacation bank to `VACATION_DAYS` once a year has passed
+    /// since the last refill (unused days do not carry over)
+    pub fn refill_vacation(&mut self, now: u64) {
+        if now.saturating_sub(self.vacation_refilled_at) >= Self::VACATION_REFILL_MICROS {
+            self.vacation_days = Self::VACATION_DAYS;
+            self.vacation_refilled_at = now;
+        }
+    }
 }
 
 // ============================================================================
@@ -1151,24 +2967,62 @@ pub enum Operation {
     Register { username: String },
     /// Update profile settings
     UpdateProfile { username: Option<String> },
+    /// Restrict which owners may act on this chain (empty = any owner).
+    /// The first list must come from a chain owner and every list must keep
+    /// the owner playing the current room.
+    SetAllowedOwners { owners: Vec<AccountOwner> },
 
     // === Room Management ===
-    /// Create a new game room
-    CreateRoom { game_type: GameType },
+    /// Create a new game room (chess rooms may start from a custom FEN or as Chess960)
+    CreateRoom {
+        game_type: GameType,
+        starting_fen: Option<String>,
+        chess960: Option<bool>,
+        /// Defaults to rated; unrated rooms allow takebacks
+        rated: Option<bool>,
+        /// Clock for both players in seconds (correspondence: seconds per
+        /// move, 86400 per day); None plays untimed
+        time_control: Option<TimeControl>,
+    },
     /// Join an existing room by host chain ID
     JoinRoom { host_chain_id: String },
-    /// Leave the current room
+    /// Leave the current room (forfeits a game still in progress)
     LeaveRoom,
-    /// Clear finished room state
+    /// Clear finished room state (forfeits a game still in progress)
     ClearRoom,
 
     // === Gameplay ===
     /// Make a move (turn-based, direct - no commit/reveal)
     MakeMove { move_data: MoveData },
+    /// Claim a chess draw by the fifty-move rule or threefold repetition
+    ClaimDraw,
+    /// Resign the current game; the opponent wins by forfeit
+    Resign,
+    /// Win on time once the opponent's clock (or correspondence deadline)
+    /// has run out
+    ClaimTimeout,
+    /// Spend banked vacation days to extend your correspondence deadline
+    /// (the bank refills to 30 days once a year)
+    TakeVacation { days: u64 },
+    /// Offer a draw (while the opponent is to move)
+    OfferDraw,
+    /// Accept the opponent's draw offer (on your turn)
+    AcceptDraw,
+    /// Decline the opponent's draw offer
+    DeclineDraw,
+    /// Ask the opponent to undo the last `plies` moves (unrated rooms only)
+    RequestTakeback { plies: u64 },
+    /// Approve the opponent's takeback request (on your turn)
+    AcceptTakeback,
+    /// Ask the hub chain to arbitrate the current room
+    OpenDispute,
 
     // === Sync ===
     /// Process inbox (no-op mutation to trigger block proposal)
     SyncInbox,
+    /// Ask the opponent's chain (or the host, if we have no room yet) for
+    /// the moves we are missing
+    RequestResync,
 }
 
 // ============================================================================
@@ -1185,17 +3039,18 @@ pub enum Message {
         joiner_username: String,
     },
 
-    /// Host sends full game state to joiner (on join and after moves)
+    /// Host sends the initial game state to the joiner
     GameStateSync { room: GameRoom },
 
-    /// Active player sends move to opponent's chain
-    GameMoveSync { room: GameRoom },
+    /// Active player sends move to opponent's chain, which replays it
+    GameMoveSync { room_id: String, record: MoveRecord },
 
-    /// Match ended notification
+    /// Match ended without a move (a resignation, forfeit or claimed draw);
+    /// the receiver only accepts its opponent conceding or a draw its own
+    /// board confirms
     MatchEnded {
         winner: Option<Player>,
         reason: String,
-        final_room: GameRoom,
     },
 
     /// Player left notification
@@ -1204,13 +3059,65 @@ pub enum Message {
         player_wallet: String,
     },
 
-    /// Reward sync (XP/coins)
+    /// Reward sync (XP/coins) for a finished room
     RewardSync {
+        /// Room the reward was earned in (see `GameRoom::room_id`)
+        room_id: String,
         player_wallet: String,
         xp_earned: u64,
         coins_earned: u64,
         is_winner: bool,
     },
+
+    /// Draw offered by the sender
+    DrawOffered { room_id: String },
+
+    /// Sender accepted our draw offer; the game ends drawn
+    DrawAccepted { room_id: String },
+
+    /// Sender declined our draw offer
+    DrawDeclined { room_id: String },
+
+    /// Sender asks to undo the last `plies` moves from `sequence`
+    TakebackRequested {
+        room_id: String,
+        plies: u64,
+        sequence: u64,
+    },
+
+    /// Sender approved our takeback request at `timestamp`, when the
+    /// clocks restart
+    TakebackAccepted {
+        room_id: String,
+        plies: u64,
+        sequence: u64,
+        timestamp: u64,
+    },
+
+    /// Sender extended their running correspondence clock
+    VacationTaken { room_id: String, days: u64 },
+
+    /// A player opened a dispute; the opponent's chain submits its own log
+    DisputeOpened { room_id: String },
+
+    /// A player chain submits its copy of the room to the hub
+    DisputeSubmission { room: GameRoom },
+
+    /// Hub's ruling, sent to both player chains
+    DisputeRuling { verdict: DisputeVerdict },
+
+    /// A lagging player asks for every move after `since`
+    /// (`room_id` is None when the joiner never received the room)
+    ResyncRequest {
+        room_id: Option<String>,
+        since: u64,
+    },
+
+    /// Moves after the requested sequence number, for the requester to replay
+    ResyncReply {
+        room_id: String,
+        records: Vec<MoveRecord>,
+    },
 }
 
 // ============================================================================
@@ -1281,6 +3188,21 @@ pub enum ChainCyclesError {
     NotInRoom,
     CannotJoinOwnRoom,
     GameAlreadyStarted,
+    DrawNotClaimable,
+    InvalidFen(String),
+    NoOpponent,
+    NoDrawOffer,
+    DrawAlreadyOffered,
+    RatedRoom,
+    InvalidTimeControl,
+    TimeExpired,
+    TimeNotExpired,
+    NotCorrespondence,
+    NoVacationLeft,
+    InvalidTakeback,
+    NoTakebackRequest,
+    OwnerNotAllowed,
+    NotRoomOwner,
     InternalError(String),
 }
 
@@ -1343,4 +3265,56 @@ impl Rewards {
 
     pub const DRAW_XP: u64 = 50;
     pub const DRAW_COINS: u64 = 25;
+
+    /// (winner XP, winner coins, loser XP, loser coins) for a game type
+    pub fn for_game(game_type: GameType) -> (u64, u64, u64, u64) {
+        match game_type {
+            GameType::Chess => (
+                Self::CHESS_WINNER_XP,
+                Self::CHESS_WINNER_COINS,
+                Self::CHESS_LOSER_XP,
+                Self::CHESS_LOSER_COINS,
+            ),
+            GameType::ConnectFour => (
+                Self::CONNECT_FOUR_WINNER_XP,
+                Self::CONNECT_FOUR_WINNER_COINS,
+                Self::CONNECT_FOUR_LOSER_XP,
+                Self::CONNECT_FOUR_LOSER_COINS,
+            ),
+            GameType::Reversi => (
+                Self::REVERSI_WINNER_XP,
+                Self::REVERSI_WINNER_COINS,
+                Self::REVERSI_LOSER_XP,
+                Self::REVERSI_LOSER_COINS,
+            ),
+            GameType::Gomoku => (
+                Self::GOMOKU_WINNER_XP,
+                Self::GOMOKU_WINNER_COINS,
+                Self::GOMOKU_LOSER_XP,
+                Self::GOMOKU_LOSER_COINS,
+            ),
+            GameType::Battleship => (
+                Self::BATTLESHIP_WINNER_XP,
+                Self::BATTLESHIP_WINNER_COINS,
+                Self::BATTLESHIP_LOSER_XP,
+                Self::BATTLESHIP_LOSER_COINS,
+            ),
+            GameType::Mancala => (
+                Self::MANCALA_WINNER_XP,
+                Self::MANCALA_WINNER_COINS,
+                Self::MANCALA_LOSER_XP,
+                Self::MANCALA_LOSER_COINS,
+            ),
+        }
+    }
+
+    /// (XP, coins, is_winner) earned by the player at `index` in a finished room
+    pub fn for_player(room: &GameRoom, index: usize) -> (u64, u64, bool) {
+        let (winner_xp, winner_coins, loser_xp, loser_coins) = Self::for_game(room.game_type);
+        match room.winner {
+            Some(w) if w.index() == index => (winner_xp, winner_coins, true),
+            Some(_) => (loser_xp, loser_coins, false),
+            None => (Self::DRAW_XP, Self::DRAW_COINS, false),
+        }
+    }
 }
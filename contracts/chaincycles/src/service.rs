@@ -8,11 +8,17 @@ mod state;
 use std::sync::Arc;
 
 use async_graphql::{EmptySubscription, Object, Request, Response, Schema};
-use linera_sdk::{linera_base_types::WithServiceAbi, views::View, Service, ServiceRuntime};
+use linera_sdk::{
+    linera_base_types::{AccountOwner, WithServiceAbi},
+    views::View,
+    Service, ServiceRuntime,
+};
 
 use chaincycles::{
-    BattleshipBoard, ChainCyclesAbi, ChessBoard, ConnectFourBoard, GameRoom, GameStatus, GameType,
-    GomokuBoard, MancalaBoard, MoveData, Player, PlayerProfile, ReversiBoard,
+    parse_square, square_name, BattleshipBoard, ChainCyclesAbi, ChessBoard, ConnectFourBoard,
+    DisputeVerdict, GameRoom, GameStatus, GameType, GomokuBoard, MancalaBoard, MoveData,
+    MoveRecord, Player, PlayerDeadline, PlayerProfile, ReversiBoard, RewardPayout, RoomDivergence,
+    RoomIntegrity, TimeControl,
 };
 use state::ChainCyclesState;
 
@@ -93,6 +99,80 @@ impl QueryRoot {
         self.state.recent_rooms.get().clone()
     }
 
+    /// Get the owner who created or joined the current room
+    async fn room_owner(&self) -> Option<AccountOwner> {
+        *self.state.room_owner.get()
+    }
+
+    /// Get the owners allowed to operate this chain (empty = any owner)
+    async fn allowed_owners(&self) -> Vec<AccountOwner> {
+        self.state.allowed_owners.get().clone()
+    }
+
+    /// Get the room's hash chain head and whether the opponent agrees with it
+    async fn room_integrity(&self) -> Option<RoomIntegrity> {
+        let room = self.state.game_room.get().as_ref()?;
+        let divergence = self
+            .state
+            .room_divergence
+            .get()
+            .clone()
+            .filter(|d| d.room_id == room.room_id());
+
+        Some(RoomIntegrity {
+            room_id: room.room_id(),
+            moves: room.sequence,
+            state_hash: room.state_hash.clone(),
+            in_sync: divergence.is_none(),
+            divergence,
+        })
+    }
+
+    /// Get each player's clock and, for the player to move, their deadline
+    /// (empty for untimed rooms)
+    async fn deadlines(&self) -> Vec<PlayerDeadline> {
+        let room = match self.state.game_room.get().as_ref() {
+            Some(r) if r.time_control.is_some() => r,
+            _ => return Vec::new(),
+        };
+        let now = self.runtime.system_time().micros();
+
+        [Player::One, Player::Two]
+            .into_iter()
+            .take(room.player_wallets.len())
+            .map(|player| PlayerDeadline {
+                player,
+                username: room
+                    .usernames
+                    .get(player.index())
+                    .cloned()
+                    .unwrap_or_default(),
+                time_left: room.time_left(player, now).unwrap_or(0),
+                deadline: room.deadline(player),
+                vacation_days: room.vacation_taken(player),
+            })
+            .collect()
+    }
+
+    /// Get the hub's ruling on a disputed room (defaults to the current room)
+    async fn dispute_verdict(&self, room_id: Option<String>) -> Option<DisputeVerdict> {
+        let room_id = match room_id {
+            Some(id) => id,
+            None => self.state.game_room.get().as_ref()?.room_id(),
+        };
+        self.state
+            .dispute_verdicts
+            .get(&room_id)
+            .await
+            .ok()
+            .flatten()
+    }
+
+    /// Get recently rejected cross-chain messages (newest first)
+    async fn rejected_messages(&self) -> Vec<String> {
+        self.state.rejected_messages.get().clone()
+    }
+
     // ========================================================================
     // GAME STATE QUERIES
     // ========================================================================
@@ -158,9 +238,12 @@ impl QueryRoot {
     // PLAYER QUERIES
     // ========================================================================
 
-    /// Get player profile by wallet address
+    /// Get player profile by wallet address (with any vacation refill that
+    /// is due already counted)
     async fn player(&self, wallet: String) -> Option<PlayerProfile> {
-        self.state.players.get(&wallet).await.ok().flatten()
+        let mut profile = self.state.players.get(&wallet).await.ok().flatten()?;
+        profile.refill_vacation(self.runtime.system_time().micros());
+        Some(profile)
     }
 
     /// Check if it's a specific player's turn (by wallet)
@@ -206,6 +289,20 @@ impl QueryRoot {
             .and_then(|r| r.chess_board.clone())
     }
 
+    /// Export the chess game as PGN
+    async fn chess_pgn(&self) -> Option<String> {
+        self.state
+            .game_room
+            .get()
+            .as_ref()
+            .and_then(|r| r.chess_pgn())
+    }
+
+    /// Validate a PGN game by replaying it, returning the final board
+    async fn chess_import_pgn(&self, pgn: String) -> async_graphql::Result<ChessBoard> {
+        ChessBoard::from_pgn(&pgn).map_err(async_graphql::Error::new)
+    }
+
     /// Get Connect Four board state
     async fn connect_four_board(&self) -> Option<ConnectFourBoard> {
         self.state
@@ -273,20 +370,65 @@ impl QueryRoot {
                     _ => return false,
                 };
 
-                // In Battleship setup phase, both players can place ships
-                if r.game_type == GameType::Battleship {
-                    if let Some(board) = &r.battleship_board {
-                        if board.setup_phase {
-                            return true;
-                        }
-                    }
-                }
-
                 r.current_turn == player
             })
             .unwrap_or(false)
     }
 
+    /// Get the chess draw the current position allows a player to claim, if any
+    async fn chess_claimable_draw(&self) -> Option<String> {
+        self.state.game_room.get().as_ref().and_then(|r| {
+            r.chess_board
+                .as_ref()
+                .and_then(|board| board.claimable_draw())
+                .map(|claim| claim.reason().to_string())
+        })
+    }
+
+    /// Get chess legal moves in UCI notation, optionally only those from one square (e.g., "e2")
+    async fn chess_legal_moves(&self, from: Option<String>) -> Option<Vec<String>> {
+        let from_idx = match from {
+            Some(name) => Some(parse_square(&name)?),
+            None => None,
+        };
+        self.state.game_room.get().as_ref().and_then(|r| {
+            r.chess_board.as_ref().map(|board| {
+                board
+                    .legal_moves()
+                    .into_iter()
+                    .filter(|m| from_idx.is_none_or(|idx| m.from == idx))
+                    .map(|m| board.move_to_uci(&m))
+                    .collect()
+            })
+        })
+    }
+
+    /// Check if the side to move is in check
+    async fn chess_in_check(&self) -> Option<bool> {
+        self.state
+            .game_room
+            .get()
+            .as_ref()
+            .and_then(|r| r.chess_board.as_ref().map(|board| board.is_in_check()))
+    }
+
+    /// Get squares attacked by a player (Player One = white), defaulting to the side not to move
+    async fn chess_attacked_squares(&self, player: Option<Player>) -> Option<Vec<String>> {
+        self.state.game_room.get().as_ref().and_then(|r| {
+            r.chess_board.as_ref().map(|board| {
+                let by_white = match player {
+                    Some(p) => p == Player::One,
+                    None => !board.white_turn,
+                };
+                board
+                    .attacked_squares(by_white)
+                    .into_iter()
+                    .map(square_name)
+                    .collect()
+            })
+        })
+    }
+
     /// Get Connect Four valid columns (returns array of column indices that aren't full)
     async fn connect_four_valid_columns(&self) -> Option<Vec<u8>> {
         self.state.game_room.get().as_ref().and_then(|r| {
@@ -362,15 +504,38 @@ impl MutationRoot {
         []
     }
 
+    /// Restrict which owners may act on this chain (empty list = any owner)
+    async fn set_allowed_owners(&self, owners: Vec<AccountOwner>) -> [u8; 0] {
+        use chaincycles::Operation;
+        self.runtime
+            .schedule_operation(&Operation::SetAllowedOwners { owners });
+        []
+    }
+
     // ========================================================================
     // ROOM MANAGEMENT
     // ========================================================================
 
     /// Create a new game room with specified game type
-    async fn create_room(&self, game_type: GameType) -> [u8; 0] {
+    /// (chess rooms may pass a starting FEN for drills and endgame practice,
+    /// or request a Chess960 back-rank shuffle); time controls are in
+    /// seconds, and correspondence rooms give seconds per move (86400 per day)
+    async fn create_room(
+        &self,
+        game_type: GameType,
+        starting_fen: Option<String>,
+        chess960: Option<bool>,
+        rated: Option<bool>,
+        time_control: Option<TimeControl>,
+    ) -> [u8; 0] {
         use chaincycles::Operation;
-        self.runtime
-            .schedule_operation(&Operation::CreateRoom { game_type });
+        self.runtime.schedule_operation(&Operation::CreateRoom {
+            game_type,
+            starting_fen,
+            chess960,
+            rated,
+            time_control,
+        });
         []
     }
 
@@ -382,14 +547,14 @@ impl MutationRoot {
         []
     }
 
-    /// Leave current room
+    /// Leave current room (forfeits a game still in progress)
     async fn leave_room(&self) -> [u8; 0] {
         use chaincycles::Operation;
         self.runtime.schedule_operation(&Operation::LeaveRoom);
         []
     }
 
-    /// Clear finished room
+    /// Clear finished room (forfeits a game still in progress)
     async fn clear_room(&self) -> [u8; 0] {
         use chaincycles::Operation;
         self.runtime.schedule_operation(&Operation::ClearRoom);
@@ -401,12 +566,13 @@ impl MutationRoot {
     // ========================================================================
 
     /// Make a move (unified for all games)
-    /// - Chess: primary ignored, secondary = UCI move string (e.g., "e2e4")
+    /// - Chess: primary ignored, secondary = UCI ("e2e4", "e7e8q") or SAN ("Nf3", "O-O")
     /// - Connect Four: primary = column (0-6), secondary ignored
     /// - Reversi: primary = position (0-63), secondary ignored (-1 to pass)
     /// - Gomoku: primary = position (0-224 for 15x15), secondary ignored
-    /// - Battleship setup: secondary = ship placement JSON
+    /// - Battleship setup: secondary = fleet commitment (prefer `placeFleet`)
     /// - Battleship attack: primary = target position (0-99)
+    /// - Battleship answer/reveal: sent automatically after `placeFleet`
     /// - Mancala: primary = pit index (0-5 for current player)
     async fn make_move(&self, primary: i32, secondary: Option<String>) -> [u8; 0] {
         use chaincycles::Operation;
@@ -416,10 +582,91 @@ impl MutationRoot {
         []
     }
 
+    /// Claim a chess draw (fifty-move rule or threefold repetition)
+    async fn claim_draw(&self) -> [u8; 0] {
+        use chaincycles::Operation;
+        self.runtime.schedule_operation(&Operation::ClaimDraw);
+        []
+    }
+
+    /// Win on time once the opponent's clock (or correspondence deadline)
+    /// has run out
+    async fn claim_timeout(&self) -> [u8; 0] {
+        use chaincycles::Operation;
+        self.runtime.schedule_operation(&Operation::ClaimTimeout);
+        []
+    }
+
+    /// Spend banked vacation days to extend your correspondence deadline
+    /// (the bank refills to 30 days once a year)
+    async fn take_vacation(&self, days: u64) -> [u8; 0] {
+        use chaincycles::Operation;
+        self.runtime
+            .schedule_operation(&Operation::TakeVacation { days });
+        []
+    }
+
+    /// Resign the current game (the opponent wins by forfeit)
+    async fn resign(&self) -> [u8; 0] {
+        use chaincycles::Operation;
+        self.runtime.schedule_operation(&Operation::Resign);
+        []
+    }
+
+    /// Offer a draw (while the opponent is to move)
+    async fn offer_draw(&self) -> [u8; 0] {
+        use chaincycles::Operation;
+        self.runtime.schedule_operation(&Operation::OfferDraw);
+        []
+    }
+
+    /// Accept the opponent's draw offer (on your turn)
+    async fn accept_draw(&self) -> [u8; 0] {
+        use chaincycles::Operation;
+        self.runtime.schedule_operation(&Operation::AcceptDraw);
+        []
+    }
+
+    /// Decline the opponent's draw offer
+    async fn decline_draw(&self) -> [u8; 0] {
+        use chaincycles::Operation;
+        self.runtime.schedule_operation(&Operation::DeclineDraw);
+        []
+    }
+
+    /// Ask the opponent to undo the last `plies` moves (unrated rooms only)
+    async fn request_takeback(&self, plies: u64) -> [u8; 0] {
+        use chaincycles::Operation;
+        self.runtime
+            .schedule_operation(&Operation::RequestTakeback { plies });
+        []
+    }
+
+    /// Approve the opponent's takeback request (on your turn)
+    async fn accept_takeback(&self) -> [u8; 0] {
+        use chaincycles::Operation;
+        self.runtime.schedule_operation(&Operation::AcceptTakeback);
+        []
+    }
+
+    /// Ask the hub chain to arbitrate the current room
+    async fn open_dispute(&self) -> [u8; 0] {
+        use chaincycles::Operation;
+        self.runtime.schedule_operation(&Operation::OpenDispute);
+        []
+    }
+
     // ========================================================================
     // SYNC
     // ========================================================================
 
+    /// Ask the opponent's chain for any moves this chain has missed
+    async fn request_resync(&self) -> [u8; 0] {
+        use chaincycles::Operation;
+        self.runtime.schedule_operation(&Operation::RequestResync);
+        []
+    }
+
     /// Sync inbox (process pending cross-chain messages)
     async fn sync_inbox(&self) -> [u8; 0] {
         use chaincycles::Operation;
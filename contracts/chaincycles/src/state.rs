@@ -1,7 +1,8 @@
 // ChainCycles - State Storage
 // Persistent on-chain state using Linera views
 
-use crate::{GameRoom, PlayerProfile};
+use crate::{DisputeVerdict, GameRoom, MoveRecord, PlayerProfile, RewardPayout, RoomDivergence};
+use linera_sdk::linera_base_types::AccountOwner;
 use linera_sdk::views::{linera_views, MapView, RegisterView, RootView, ViewStorageContext};
 
 /// Root state for ChainCycles application
@@ -13,7 +14,7 @@ pub struct ChainCyclesState {
 
     /// Current game room on this chain (one room per chain)
     /// Both host and joiner store identical room state
-    /// Synchronized via GameMoveSync cross-chain messages
+    /// Kept in sync by replaying GameMoveSync moves on each chain
     pub game_room: RegisterView<Option<GameRoom>>,
 
     /// Player profiles indexed by wallet address string
@@ -25,6 +26,33 @@ pub struct ChainCyclesState {
     /// Host chain ID if this chain has joined another room
     pub joined_host_chain: RegisterView<Option<String>>,
 
+    /// Owner who created or joined the current room; only they may act on it
+    pub room_owner: RegisterView<Option<AccountOwner>>,
+
+    /// Owners allowed to operate this chain (empty = any owner)
+    pub allowed_owners: RegisterView<Vec<AccountOwner>>,
+
     /// Recent room codes visited (for lobby feature)
     pub recent_rooms: RegisterView<Vec<String>>,
+
+    /// Rewards already paid out, keyed by "room_id/wallet"
+    pub claimed_rewards: MapView<String, RewardPayout>,
+
+    /// Finished rooms cleared before their reward arrived, keyed by room id
+    pub unpaid_rooms: MapView<String, GameRoom>,
+
+    /// Hub only: room copies awaiting the other player's, keyed by "room_id/chain_id"
+    pub disputes: MapView<String, GameRoom>,
+
+    /// Dispute rulings issued (hub) or received (player chains), keyed by room id
+    pub dispute_verdicts: MapView<String, DisputeVerdict>,
+
+    /// Opponent moves that arrived ahead of a gap, waiting to be applied
+    pub pending_moves: RegisterView<Vec<MoveRecord>>,
+
+    /// First detected disagreement with the opponent's copy of the room
+    pub room_divergence: RegisterView<Option<RoomDivergence>>,
+
+    /// Recently rejected cross-chain messages, newest first
+    pub rejected_messages: RegisterView<Vec<String>>,
 }
@@ -0,0 +1,129 @@
+// ChainCycles - Battleship commit-reveal protocol
+// Fleets stay hidden behind salted commitments until the final reveal
+
+use chaincycles::{BattleshipBoard, Player};
+
+/// Every ship laid horizontally from column 0, one per row (rows 0-4)
+const FLEET: &str = "1,0,h;2,10,h;3,20,h;4,30,h;5,40,h";
+const P1_SALT: &str = "p1salt";
+const P2_SALT: &str = "p2salt";
+
+fn opening(salt: &str) -> String {
+    format!("{}:{}", salt, FLEET)
+}
+
+/// Board with both fleets committed, ready for the first shot
+fn committed_board() -> BattleshipBoard {
+    let mut board = BattleshipBoard::new();
+    assert!(board.commit_fleet(
+        Player::One,
+        &BattleshipBoard::fleet_commitment(P1_SALT, FLEET)
+    ));
+    assert!(board.commit_fleet(
+        Player::Two,
+        &BattleshipBoard::fleet_commitment(P2_SALT, FLEET)
+    ));
+    board
+}
+
+/// Player One fires along the rows, Player Two from the far corner back;
+/// Player Two answers the shot at `lie_at` with "miss" regardless
+fn play_until_reveal(board: &mut BattleshipBoard, lie_at: Option<u8>) {
+    let ships = BattleshipBoard::parse_fleet(FLEET).expect("valid fleet");
+    for i in 0..100u8 {
+        assert!(board.attack(Player::One, i));
+        let honest = BattleshipBoard::answer_for(&ships, &board.p2_hits, i);
+        let answer = if lie_at == Some(i) { "miss" } else { honest };
+        assert!(board.answer(Player::Two, i, answer));
+        if board.reveal_phase {
+            return;
+        }
+
+        let target = 99 - i;
+        assert!(board.attack(Player::Two, target));
+        let answer = BattleshipBoard::answer_for(&ships, &board.p1_hits, target);
+        assert!(board.answer(Player::One, target, answer));
+        if board.reveal_phase {
+            return;
+        }
+    }
+    panic!("game never reached the reveal phase");
+}
+
+#[test]
+fn setup_commits_in_turn_player_one_first() {
+    let mut board = BattleshipBoard::new();
+    let p1 = BattleshipBoard::fleet_commitment(P1_SALT, FLEET);
+    let p2 = BattleshipBoard::fleet_commitment(P2_SALT, FLEET);
+
+    assert!(
+        !board.commit_fleet(Player::Two, &p2),
+        "Player Two may not commit first"
+    );
+    assert!(
+        !board.commit_fleet(Player::One, FLEET),
+        "plaintext is not a commitment"
+    );
+    assert!(!board.attack(Player::One, 0), "no shots during setup");
+
+    assert!(board.commit_fleet(Player::One, &p1));
+    assert!(
+        !board.commit_fleet(Player::One, &p1),
+        "a commitment cannot be replaced"
+    );
+    assert!(board.setup_phase);
+
+    assert!(board.commit_fleet(Player::Two, &p2));
+    assert!(!board.setup_phase);
+    assert!(board.p1_ships.is_empty() && board.p2_ships.is_empty());
+}
+
+#[test]
+fn honest_reveal_settles_the_game() {
+    let mut board = committed_board();
+    play_until_reveal(&mut board, None);
+    assert_eq!(board.ships_sunk, vec![0, 5]);
+
+    assert!(board.reveal(Player::One, &opening(P1_SALT)));
+    assert!(
+        !board.reveal(Player::One, &opening(P1_SALT)),
+        "only one reveal"
+    );
+    assert_eq!(board.result(), None, "waits for both reveals");
+
+    assert!(board.reveal(Player::Two, &opening(P2_SALT)));
+    assert_eq!(board.honest, vec![true, true]);
+    assert_eq!(board.p2_ships, BattleshipBoard::parse_fleet(FLEET).unwrap());
+    assert_eq!(board.result(), Some((Some(Player::One), "fleet_sunk")));
+}
+
+#[test]
+fn reveal_not_matching_the_commitment_forfeits() {
+    let mut board = committed_board();
+    assert!(
+        !board.reveal(Player::One, &opening(P1_SALT)),
+        "no reveal mid-game"
+    );
+    play_until_reveal(&mut board, None);
+
+    assert!(board.reveal(Player::One, &opening(P1_SALT)));
+    // Same fleet, wrong salt: the commitment does not open
+    assert!(board.reveal(Player::Two, &opening("other")));
+    assert_eq!(board.honest, vec![true, false]);
+    assert!(board.p2_ships.is_empty());
+    assert_eq!(board.result(), Some((Some(Player::One), "false_answers")));
+}
+
+#[test]
+fn lying_answer_is_caught_at_the_reveal() {
+    let mut board = committed_board();
+    // Player Two calls the first hit on their carrier a miss, so it can
+    // never be sunk and Player One's fleet goes down first
+    play_until_reveal(&mut board, Some(0));
+    assert_eq!(board.ships_sunk, vec![5, 4]);
+
+    assert!(board.reveal(Player::One, &opening(P1_SALT)));
+    assert!(board.reveal(Player::Two, &opening(P2_SALT)));
+    assert_eq!(board.honest, vec![true, false]);
+    assert_eq!(board.result(), Some((Some(Player::One), "false_answers")));
+}
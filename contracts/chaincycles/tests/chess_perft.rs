@@ -0,0 +1,107 @@
+// ChainCycles - Chess move generator perft tests
+// Leaf-node counts from the standard perft positions (chessprogramming.org)
+
+use chaincycles::ChessBoard;
+
+const START: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+const KIWIPETE: &str = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+const POSITION_3: &str = "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1";
+const POSITION_4: &str = "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1";
+const POSITION_4_MIRRORED: &str = "r2q1rk1/pP1p2pp/Q4n2/bbp1p3/Np6/1B3NBn/pPPP1PPP/R3K2R b KQ - 0 1";
+const POSITION_5: &str = "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8";
+const POSITION_6: &str =
+    "r4rk1/1pp1qppp/p1np1n2/2b1p1B1/2B1P1b1/P1NP1N2/1PP1QPPP/R4RK1 w - - 0 10";
+
+/// Assert perft counts for depths 1..=expected.len()
+fn assert_perft(fen: &str, expected: &[u64]) {
+    let board = ChessBoard::from_fen(fen).expect("valid perft FEN");
+    for (i, &nodes) in expected.iter().enumerate() {
+        let depth = i as u32 + 1;
+        assert_eq!(board.perft(depth), nodes, "perft({}) of {}", depth, fen);
+    }
+}
+
+#[test]
+fn perft_start_position() {
+    assert_perft(START, &[20, 400, 8_902, 197_281]);
+}
+
+#[test]
+fn perft_kiwipete() {
+    assert_perft(KIWIPETE, &[48, 2_039, 97_862]);
+}
+
+#[test]
+fn perft_position_3() {
+    assert_perft(POSITION_3, &[14, 191, 2_812, 43_238]);
+}
+
+#[test]
+fn perft_position_4() {
+    assert_perft(POSITION_4, &[6, 264, 9_467]);
+    assert_perft(POSITION_4_MIRRORED, &[6, 264, 9_467]);
+}
+
+#[test]
+fn perft_position_5() {
+    assert_perft(POSITION_5, &[44, 1_486, 62_379]);
+}
+
+#[test]
+fn perft_position_6() {
+    assert_perft(POSITION_6, &[46, 2_079, 89_890]);
+}
+
+#[test]
+fn perft_chess960_positions() {
+    assert_perft(
+        "bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9",
+        &[21, 528, 12_189],
+    );
+    assert_perft(
+        "2nnrbkr/p1qppppp/8/1ppb4/6PP/3PP3/PPP2P2/BQNNRBKR w HEhe - 1 9",
+        &[21, 807, 18_002],
+    );
+    assert_perft(
+        "b1q1rrkb/pppppppp/3nn3/8/P7/1PPP4/4PPPP/BQNNRKRB w GE - 1 9",
+        &[20, 479, 10_471],
+    );
+    assert_perft(
+        "qbbnnrkr/2pp2pp/p7/1p2pp2/8/P3PP2/1PPP1KPP/QBBNNR1R w hf - 0 9",
+        &[22, 593, 13_440],
+    );
+}
+
+/// Walk the tree through `make_move` so UCI formatting, castling notation,
+/// promotions and en passant all round-trip through the public API
+#[test]
+fn perft_through_uci_round_trip() {
+    fn walk(board: &ChessBoard, depth: u32) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+        board
+            .legal_moves()
+            .iter()
+            .map(|m| {
+                let uci = board.move_to_uci(m);
+                let mut next = board.clone();
+                assert!(next.make_move(&uci, board.white_turn), "rejected {}", uci);
+                walk(&next, depth - 1)
+            })
+            .sum()
+    }
+
+    for (fen, nodes) in [(KIWIPETE, 2_039), (POSITION_4, 264), (POSITION_5, 1_486)] {
+        let board = ChessBoard::from_fen(fen).unwrap();
+        assert_eq!(walk(&board, 2), nodes, "{}", fen);
+    }
+}
+
+#[test]
+#[ignore = "slow: run with --ignored"]
+fn perft_deep() {
+    assert_perft(START, &[20, 400, 8_902, 197_281, 4_865_609]);
+    assert_perft(KIWIPETE, &[48, 2_039, 97_862, 4_085_603]);
+    assert_perft(POSITION_3, &[14, 191, 2_812, 43_238, 674_624]);
+}
@@ -0,0 +1,67 @@
+// ChainCycles - PGN import and export
+// Games must survive a round trip through to_pgn/from_pgn unchanged
+
+use chaincycles::ChessBoard;
+
+/// Both sides can castle short; White's b-pawn is about to promote
+const SETUP: &str = "r3k2r/1P3p2/8/8/8/8/8/R3K2R w KQkq - 0 1";
+
+#[test]
+fn pgn_round_trip_with_castling_promotion_and_comments() {
+    let pgn = format!(
+        "[Event \"Test\"]\n[SetUp \"1\"]\n[FEN \"{}\"]\n\n\
+         1. 0-0 {{castles with zeros}} (1. Kf1 Kf8) 1... 0-0 ; line comment\n\
+         2. bxa8=Q $1 (2. b8=Q) Rxa8 *\n",
+        SETUP
+    );
+
+    let board = ChessBoard::from_pgn(&pgn).expect("valid PGN");
+    assert_eq!(board.san_history(), vec!["O-O", "O-O", "bxa8=Q", "Rxa8"]);
+    assert_eq!(board.moves, vec!["e1g1", "e8g8", "b7a8q", "f8a8"]);
+
+    let exported = board.to_pgn(&[("Event", "Test".to_string())], "*");
+    assert!(exported.contains("1. O-O O-O 2. bxa8=Q Rxa8 *"));
+
+    let reimported = ChessBoard::from_pgn(&exported).expect("exported PGN imports");
+    assert_eq!(reimported.fen, board.fen);
+    assert_eq!(reimported.moves, board.moves);
+}
+
+#[test]
+fn pgn_move_numbers_glued_to_moves() {
+    let board = ChessBoard::from_pgn("1.e4 e5 2.Nf3 2...Nc6 3.Bb5 *").expect("valid PGN");
+    assert_eq!(board.san_history(), vec!["e4", "e5", "Nf3", "Nc6", "Bb5"]);
+}
+
+#[test]
+fn pgn_rejects_illegal_moves() {
+    assert!(ChessBoard::from_pgn("1. e4 e5 2. Ke3 *").is_err());
+}
+
+#[test]
+fn over_disambiguated_and_long_algebraic_moves() {
+    let board = ChessBoard::new();
+    assert_eq!(board.parse_san("Ngf3"), board.parse_san("Nf3"));
+    assert_eq!(board.parse_san("Ng1f3"), board.parse_san("Nf3"));
+    assert_eq!(board.parse_san("Ng1-f3"), board.parse_san("Nf3"));
+    assert_eq!(board.parse_san("e2-e4"), board.parse_san("e4"));
+    assert!(board.parse_san("Nf3").is_some());
+    assert_eq!(board.parse_san("Nhf3"), None);
+    assert_eq!(board.parse_san("Nf4"), None);
+
+    let game = ChessBoard::from_pgn("1. e2e4 e7-e5 2. Ng1f3 Nb8c6 3. Bf1xb5 *");
+    assert!(game.is_err(), "Bf1xb5 is not a capture target");
+    let game = ChessBoard::from_pgn("1. e2e4 e7-e5 2. Ng1f3 Nb8c6 3. Bf1-b5 *")
+        .expect("long algebraic PGN");
+    assert_eq!(game.san_history(), vec!["e4", "e5", "Nf3", "Nc6", "Bb5"]);
+}
+
+#[test]
+fn san_history_fallback_understands_classical_castling() {
+    let mut board =
+        ChessBoard::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1").expect("valid FEN");
+    assert!(board.make_move("e1g1", true));
+    assert!(board.make_move("e8c8", false));
+    board.san_moves.clear();
+    assert_eq!(board.san_history(), vec!["O-O", "O-O-O"]);
+}
@@ -0,0 +1,154 @@
+// ChainCycles - Timeout claims and match-end routing
+// A claim ends the game on the claimer's chain; MatchEnded must reach the loser's
+
+use std::str::FromStr;
+
+use chaincycles::{ClockMode, GameRoom, GameStatus, GameType, Player, PlayerProfile, TimeControl};
+use linera_sdk::linera_base_types::{AccountOwner, ChainId, Timestamp};
+
+const SECOND: u64 = 1_000_000;
+const HOST_CHAIN: &str = "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa";
+const JOINER_CHAIN: &str = "bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb";
+
+/// Two-player room with `control`, started at t = 0 with Player One to move
+fn timed_room(control: TimeControl) -> GameRoom {
+    let host = ChainId::from_str(HOST_CHAIN).expect("valid chain id");
+    let mut room = GameRoom::new(
+        host,
+        AccountOwner::Address20([1; 20]),
+        "host".to_string(),
+        GameType::ConnectFour,
+        Timestamp::from(0),
+    );
+    room.time_control = Some(control);
+    room.clocks = room.starting_clocks();
+    room.add_joiner(
+        JOINER_CHAIN.to_string(),
+        "joiner".to_string(),
+        "joiner".to_string(),
+        Timestamp::from(0),
+    );
+    room
+}
+
+/// Claim a timeout from `claimer`'s chain at `now` the way ClaimTimeout
+/// does, returning the chain that receives MatchEnded
+fn claim_timeout(room: &mut GameRoom, claimer: Player, now: u64) -> Option<String> {
+    let loser = room.timeout_loser(claimer, now)?;
+    let claimer_chain = room.player_chain_ids[claimer.index()].clone();
+    let recipient = room.forfeit(loser, "timeout", now, &claimer_chain)?;
+    Some(recipient.to_string())
+}
+
+#[test]
+fn timeout_claim_notifies_the_loser_chain() {
+    let mut room = timed_room(TimeControl {
+        base_secs: 60,
+        bonus_secs: 2,
+        mode: ClockMode::Fischer,
+    });
+
+    // Player One is to move; Player Two claims once the clock runs out
+    assert_eq!(claim_timeout(&mut room, Player::Two, 59 * SECOND), None);
+    assert_eq!(claim_timeout(&mut room, Player::One, 60 * SECOND), None);
+    assert_eq!(room.status, GameStatus::InProgress);
+
+    assert_eq!(
+        claim_timeout(&mut room, Player::Two, 60 * SECOND).as_deref(),
+        Some(HOST_CHAIN)
+    );
+    assert_eq!(room.status, GameStatus::Forfeited);
+    assert_eq!(room.winner, Some(Player::Two));
+    assert_eq!(room.end_reason.as_deref(), Some("timeout"));
+}
+
+#[test]
+fn resigning_notifies_the_winner_chain() {
+    let mut room = timed_room(TimeControl {
+        base_secs: 60,
+        bonus_secs: 0,
+        mode: ClockMode::Fischer,
+    });
+
+    let recipient = room.forfeit(Player::Two, "resigned", 10 * SECOND, JOINER_CHAIN);
+    assert_eq!(
+        recipient.map(|c| c.to_string()).as_deref(),
+        Some(HOST_CHAIN)
+    );
+    assert_eq!(room.winner, Some(Player::One));
+}
+
+#[test]
+fn delay_postpones_the_flag() {
+    let room = timed_room(TimeControl {
+        base_secs: 60,
+        bonus_secs: 5,
+        mode: ClockMode::Delay,
+    });
+
+    assert_eq!(room.timeout_loser(Player::Two, 64 * SECOND), None);
+    assert_eq!(
+        room.timeout_loser(Player::Two, 65 * SECOND),
+        Some(Player::One)
+    );
+}
+
+#[test]
+fn correspondence_deadline_claim_notifies_the_loser_chain() {
+    let mut room = timed_room(TimeControl {
+        base_secs: 3 * 86_400,
+        bonus_secs: 0,
+        mode: ClockMode::Correspondence,
+    });
+    let day = TimeControl::DAY_MICROS;
+
+    assert_eq!(room.deadline(Player::One), Some(3 * day));
+    assert_eq!(room.deadline(Player::Two), None);
+
+    // Two vacation days push Player One's deadline back
+    room.take_vacation(Player::One, 2);
+    assert_eq!(room.deadline(Player::One), Some(5 * day));
+    assert_eq!(claim_timeout(&mut room, Player::Two, 5 * day - 1), None);
+    assert_eq!(
+        claim_timeout(&mut room, Player::Two, 5 * day).as_deref(),
+        Some(HOST_CHAIN)
+    );
+}
+
+#[test]
+fn huge_clocks_saturate_instead_of_overflowing() {
+    let control = TimeControl {
+        base_secs: u64::MAX,
+        bonus_secs: u64::MAX,
+        mode: ClockMode::Fischer,
+    };
+    assert_eq!(control.after_move(u64::MAX - 1), u64::MAX);
+
+    let mut room = timed_room(TimeControl {
+        base_secs: 1,
+        bonus_secs: 0,
+        mode: ClockMode::Correspondence,
+    });
+    room.last_move_at = u64::MAX - 1;
+    room.take_vacation(Player::One, u64::MAX);
+    room.take_vacation(Player::One, 1);
+    assert_eq!(room.vacation_taken(Player::One), u64::MAX);
+    assert_eq!(room.deadline(Player::One), Some(u64::MAX));
+}
+
+#[test]
+fn vacation_bank_refills_once_a_year() {
+    let year = PlayerProfile::VACATION_REFILL_MICROS;
+    let mut profile = PlayerProfile {
+        vacation_days: 4,
+        vacation_refilled_at: 10,
+        ..PlayerProfile::default()
+    };
+
+    profile.refill_vacation(10 + year - 1);
+    assert_eq!(profile.vacation_days, 4);
+
+    profile.refill_vacation(10 + year);
+    assert_eq!(profile.vacation_days, PlayerProfile::VACATION_DAYS);
+    assert_eq!(profile.vacation_refilled_at, 10 + year);
+}
@@ -11,6 +11,7 @@ import { BattleshipHelp } from './HelpModal';
 
 interface BattleshipGameProps {
   board: BattleshipBoard;
+  myFleet: number[];      // Our own fleet, kept in this browser until the reveal
   currentTurn?: Player; // For consistency, may be unused
   isMyTurn: boolean;
   myPlayer: Player;
@@ -27,6 +28,7 @@ interface ShipPlacement {
 
 export default function BattleshipGame({
   board,
+  myFleet,
   currentTurn: _currentTurn, // May be unused, kept for consistency
   isMyTurn,
   myPlayer,
@@ -40,14 +42,21 @@ export default function BattleshipGame({
   const [hoverPos, setHoverPos] = useState<number | null>(null);
 
   const isP1 = myPlayer === 'One';
-  const myShips = isP1 ? board.p1Ships : board.p2Ships;
+  // The shared board only carries fleets once they are revealed at the end
+  const myShips = myFleet.length > 0 ? myFleet : isP1 ? board.p1Ships : board.p2Ships;
   const oppShips = isP1 ? board.p2Ships : board.p1Ships;
   const myHits = isP1 ? board.p1Hits : board.p2Hits;
   const oppHits = isP1 ? board.p2Hits : board.p1Hits;
   const amIReady = isP1 ? board.p1Ready : board.p2Ready;
 
-  // Setup phase logic
+  // Setup phase logic: fleets are committed in turn, Player One first
   const inSetupPhase = board.setupPhase;
+  const canPlace = inSetupPhase && !amIReady && isMyTurn && !disabled;
+
+  // Our turn may be owed to answering a shot or revealing the fleet, which
+  // happens automatically; only a free turn can attack
+  const owesAnswer = board.pendingAttack !== null || board.revealPhase;
+  const canAttack = !inSetupPhase && isMyTurn && !owesAnswer && !disabled;
 
   // Check if ship placement is valid
   const isValidPlacement = (shipIdx: number, startPos: number, horizontal: boolean): boolean => {
@@ -100,7 +109,7 @@ export default function BattleshipGame({
 
   // Handle cell click during setup
   const handleSetupClick = (pos: number) => {
-    if (!inSetupPhase || amIReady || currentShipIndex >= BATTLESHIP_SHIPS.length) return;
+    if (!canPlace || currentShipIndex >= BATTLESHIP_SHIPS.length) return;
     
     if (!isValidPlacement(currentShipIndex, pos, isHorizontal)) return;
     
@@ -127,7 +136,7 @@ export default function BattleshipGame({
 
   // Handle attack click
   const handleAttackClick = (pos: number) => {
-    if (inSetupPhase || !isMyTurn || disabled) return;
+    if (!canAttack) return;
     if (myHits.includes(pos)) return; // Already attacked
     onAttack(pos);
   };
@@ -212,7 +221,11 @@ export default function BattleshipGame({
       <div className="flex flex-col items-center">
         <div className="mb-4 text-center">
           <h2 className="text-xl font-bold mb-2">Place Your Ships</h2>
-          {currentShipIndex < BATTLESHIP_SHIPS.length ? (
+          {amIReady ? (
+            <p className="text-green-400">Fleet committed! Waiting for opponent...</p>
+          ) : !isMyTurn ? (
+            <p className="text-gray-400">Waiting for opponent to place their fleet...</p>
+          ) : currentShipIndex < BATTLESHIP_SHIPS.length ? (
             <>
               <p className="text-sm text-gray-400">
                 Placing: {BATTLESHIP_SHIPS[currentShipIndex].name} 
@@ -230,7 +243,7 @@ export default function BattleshipGame({
           )}
         </div>
         
-        {renderGrid('Your Board', myShips, oppHits, handleSetupClick, true, true)}
+        {renderGrid('Your Board', myShips, oppHits, handleSetupClick, true, canPlace)}
         
         {/* Ships to place */}
         <div className="mt-4 flex flex-wrap gap-2 justify-center">
@@ -263,8 +276,14 @@ export default function BattleshipGame({
     <div className="flex flex-col items-center">
       {/* Turn indicator */}
       <div className="mb-4 text-center">
-        <span className={`text-sm ${isMyTurn ? 'text-green-400' : 'text-gray-400'}`}>
-          {isMyTurn ? "Your turn - attack!" : "Opponent's turn"}
+        <span className={`text-sm ${canAttack ? 'text-green-400' : 'text-gray-400'}`}>
+          {board.revealPhase
+            ? 'Revealing fleets...'
+            : canAttack
+            ? 'Your turn - attack!'
+            : isMyTurn
+            ? 'Answering the last shot...'
+            : "Opponent's turn"}
         </span>
       </div>
 
@@ -19,7 +19,9 @@ import type {
   GomokuBoard,
   BattleshipBoard,
   MancalaBoard,
+  Player,
 } from './types';
+import { battleshipAnswerFor, parseBattleshipFleet } from './types';
 
 // ============================================================================
 // GRAPHQL QUERIES
@@ -72,7 +74,12 @@ const QUERIES = {
           p1Ready
           p2Ready
           moves
+          answers
           shipsSunk
+          commitments
+          pendingAttack
+          revealPhase
+          revealed
         }
         mancalaBoard {
           pits
@@ -228,7 +235,12 @@ const QUERIES = {
         p1Ready
         p2Ready
         moves
+        answers
         shipsSunk
+        commitments
+        pendingAttack
+        revealPhase
+        revealed
       }
     }
   `,
@@ -476,11 +488,66 @@ export async function makeGomokuMove(position: number): Promise<void> {
 
 /**
  * Place ships in Battleship (setup phase)
- * @param shipData - JSON string describing ship placements
- * Format: [{ shipSize: number, startPos: number, horizontal: boolean }, ...]
+ * Only a salted commitment goes on chain; the placement and salt stay in
+ * this browser until the fleet is revealed at the end of the game.
+ * @param roomKey - Room the fleet belongs to (see battleshipRoomKey)
+ * @param shipData - Ship placements, format: "shipId,startPos,h|v;..."
  */
-export async function placeBattleshipShips(shipData: string): Promise<void> {
-  await makeMove({ primary: 0, secondary: shipData });
+export async function placeBattleshipShips(roomKey: string, shipData: string): Promise<void> {
+  const saltBytes = crypto.getRandomValues(new Uint8Array(16));
+  const salt = toHex(saltBytes);
+  const opening = `${salt}:${shipData}`;
+  const digest = await crypto.subtle.digest('SHA-256', new TextEncoder().encode(opening));
+  localStorage.setItem(BATTLESHIP_FLEET_PREFIX + roomKey, opening);
+  await makeMove({ primary: 0, secondary: toHex(new Uint8Array(digest)) });
+}
+
+/**
+ * Our fleet opening ("salt:placement") for a room, if placed from this browser
+ */
+export function loadBattleshipFleet(roomKey: string): string | null {
+  return localStorage.getItem(BATTLESHIP_FLEET_PREFIX + roomKey);
+}
+
+/**
+ * Forget our fleet opening once the room is gone
+ */
+export function forgetBattleshipFleet(roomKey: string): void {
+  localStorage.removeItem(BATTLESHIP_FLEET_PREFIX + roomKey);
+}
+
+/**
+ * Key identifying a room across polls (host chain + creation time)
+ */
+export function battleshipRoomKey(room: GameRoom): string {
+  return `${room.hostChainId}/${room.createdAt}`;
+}
+
+/**
+ * Move our fleet owes the opponent, if any: the answer to a pending attack
+ * on us, or the final reveal. Mirrors what the contract checks at the reveal.
+ */
+export function battleshipOwedMove(
+  board: BattleshipBoard,
+  player: Player,
+  opening: string,
+): MoveData | null {
+  const index = player === 'One' ? 0 : 1;
+  if (board.revealPhase) {
+    return board.revealed[index] ? null : { primary: 0, secondary: opening };
+  }
+
+  const ships = parseBattleshipFleet(opening.slice(opening.indexOf(':') + 1));
+  if (board.pendingAttack === null || !ships) return null;
+  const hits = player === 'One' ? board.p1Hits : board.p2Hits;
+  const pos = board.pendingAttack;
+  return { primary: pos, secondary: battleshipAnswerFor(ships, hits, pos) };
+}
+
+const BATTLESHIP_FLEET_PREFIX = 'chaincycles_battleship_fleet_';
+
+function toHex(bytes: Uint8Array): string {
+  return Array.from(bytes, (b) => b.toString(16).padStart(2, '0')).join('');
 }
 
 /**
@@ -25,7 +25,7 @@ export type GameStatus =
 
 export interface MoveData {
   primary: number;       // Column for C4, position for others, -1 for pass
-  secondary?: string;    // UCI move for chess, ship placement for battleship
+  secondary?: string;    // UCI move for chess, fleet commitment/answer/reveal for battleship
 }
 
 // ============================================================================
@@ -70,15 +70,20 @@ export interface GomokuBoard {
 
 // Battleship Board (10x10 per player)
 export interface BattleshipBoard {
-  p1Ships: number[];      // Player 1's ship positions (100 cells)
+  p1Ships: number[];      // Player 1's ship positions (empty until revealed)
   p1Hits: number[];       // Hits received on P1's board
-  p2Ships: number[];      // Player 2's ship positions (100 cells)
+  p2Ships: number[];      // Player 2's ship positions (empty until revealed)
   p2Hits: number[];       // Hits received on P2's board
   setupPhase: boolean;
   p1Ready: boolean;
   p2Ready: boolean;
   moves: number[];        // Attack history
+  answers: number[];      // Defender's answer per attack: 1=miss, 2=hit, 3=sunk
   shipsSunk: number[];    // [p1_sunk, p2_sunk]
+  commitments: string[];  // Fleet commitments [p1, p2]: SHA-256 of "salt:placement"
+  pendingAttack: number | null; // Attack waiting for the defender's answer
+  revealPhase: boolean;   // Both players must now reveal their fleets
+  revealed: boolean[];    // [p1_revealed, p2_revealed]
 }
 
 // Mancala Board
@@ -229,6 +234,44 @@ export const BATTLESHIP_SHIPS = [
   { name: 'Destroyer', size: 2 },
 ];
 
+// Parse a fleet placement ("shipId,startPos,h|v;...") into 100 cells
+// (0=water, 1-5=ship id), mirroring BattleshipBoard::parse_fleet
+export function parseBattleshipFleet(shipData: string): number[] | null {
+  const cells = new Array<number>(100).fill(0);
+  const placed = new Set<number>();
+
+  for (const shipStr of shipData.split(';')) {
+    const [id, start, dir] = shipStr.split(',');
+    const shipId = Number(id);
+    const startPos = Number(start);
+    const horizontal = dir === 'h';
+    if (!(shipId >= 1 && shipId <= 5) || !(startPos >= 0 && startPos < 100)) return null;
+    if (placed.has(shipId)) return null;
+    placed.add(shipId);
+
+    const size = BATTLESHIP_SHIPS[shipId - 1].size;
+    const [row, col] = battleshipPosToRowCol(startPos);
+    if ((horizontal ? col : row) + size > 10) return null;
+
+    for (let i = 0; i < size; i++) {
+      const pos = horizontal ? startPos + i : startPos + i * 10;
+      if (cells[pos] !== 0) return null;
+      cells[pos] = shipId;
+    }
+  }
+
+  return placed.size === BATTLESHIP_SHIPS.length ? cells : null;
+}
+
+// Answer a shot against our fleet, given the shots already taken at it
+// (mirrors BattleshipBoard::answer_for)
+export function battleshipAnswerFor(ships: number[], hits: number[], pos: number): string {
+  const shipId = ships[pos];
+  if (shipId === 0) return 'miss';
+  const sunk = ships.every((id, i) => id !== shipId || i === pos || hits[i] === 2);
+  return sunk ? 'sunk' : 'hit';
+}
+
 // ============================================================================
 // MANCALA UTILITIES
 // ============================================================================
@@ -1,12 +1,17 @@
 // Game Page - Renders the appropriate game based on room type
-import { useEffect, useState } from 'react';
+import { useEffect, useRef, useState } from 'react';
 import { useParams, useNavigate } from 'react-router-dom';
 import { motion, AnimatePresence } from 'framer-motion';
 import { Copy, Check, LogOut, Users, Trophy } from 'lucide-react';
 import { useLineraStore } from '../stores/lineraStore';
 import { useGameStore } from '../stores/gameStore';
-import { GAME_INFO, type Player, type MoveData } from '../lib/types';
-import { getReversiValidMoves } from '../lib/gameApi';
+import { GAME_INFO, parseBattleshipFleet, type Player, type MoveData } from '../lib/types';
+import {
+  battleshipOwedMove,
+  battleshipRoomKey,
+  getReversiValidMoves,
+  loadBattleshipFleet,
+} from '../lib/gameApi';
 import {
   ChessGame,
   ConnectFourGame,
@@ -28,6 +33,7 @@ export default function GamePage() {
     joinExistingRoom,
     leaveCurrentRoom,
     submitMove,
+    placeFleet,
     startPolling,
     stopPolling,
     syncRoom,
@@ -35,6 +41,7 @@ export default function GamePage() {
   
   const [copied, setCopied] = useState(false);
   const [reversiValidMoves, setReversiValidMoves] = useState<number[]>([]);
+  const lastOwedMove = useRef<string | null>(null);
   
   // Determine player info
   const playerIndex = room?.playerChainIds.indexOf(chainId || '') ?? -1;
@@ -85,6 +92,28 @@ export default function GamePage() {
     }
   }, [room?.gameType, isMyTurn, room?.status, room?.reversiBoard?.moves.length]);
   
+  // Our Battleship fleet never leaves this browser; only its commitment is on chain
+  const fleetOpening = room?.gameType === 'Battleship' && isPlayer
+    ? loadBattleshipFleet(battleshipRoomKey(room))
+    : null;
+  const battleshipFleet = fleetOpening
+    ? parseBattleshipFleet(fleetOpening.slice(fleetOpening.indexOf(':') + 1)) ?? []
+    : [];
+  
+  // Answer the opponent's shots and reveal our fleet at the end
+  const battleshipBoard = room?.battleshipBoard;
+  useEffect(() => {
+    if (!battleshipBoard || !fleetOpening || !isMyTurn) return;
+    const owed = battleshipOwedMove(battleshipBoard, myPlayer, fleetOpening);
+    const key = `${battleshipBoard.moves.length}/${battleshipBoard.revealPhase}`;
+    if (!owed || lastOwedMove.current === key) return;
+    lastOwedMove.current = key;
+    submitMove(owed).catch((err) => {
+      lastOwedMove.current = null;
+      console.error('Battleship answer failed:', err);
+    });
+  }, [battleshipBoard, fleetOpening, isMyTurn, myPlayer]);
+  
   const copyRoomId = () => {
     if (hostChainId) {
       navigator.clipboard.writeText(hostChainId);
@@ -107,6 +136,14 @@ export default function GamePage() {
     }
   };
   
+  const handlePlaceFleet = async (shipData: string) => {
+    try {
+      await placeFleet(shipData);
+    } catch (err) {
+      console.error('Fleet placement failed:', err);
+    }
+  };
+  
   // Render the appropriate game board
   const renderGame = () => {
     if (!room || !isPlayer) return null;
@@ -171,11 +208,12 @@ export default function GamePage() {
         return (
           <BattleshipGame
             board={room.battleshipBoard}
+            myFleet={battleshipFleet}
             currentTurn={room.currentTurn}
             isMyTurn={isMyTurn}
             myPlayer={myPlayer}
             onAttack={(pos) => handleMove({ primary: pos })}
-            onPlaceShips={(data) => handleMove({ primary: 0, secondary: data })}
+            onPlaceShips={handlePlaceFleet}
             disabled={isLoading}
           />
         );
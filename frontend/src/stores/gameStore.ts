@@ -10,6 +10,9 @@ import {
   getRoom, 
   getRoomSynced,
   makeMove,
+  placeBattleshipShips,
+  battleshipRoomKey,
+  forgetBattleshipFleet,
   pollRoomUpdates,
 } from '../lib/gameApi';
 
@@ -34,6 +37,9 @@ interface GameState {
   // Gameplay - unified move action
   submitMove: (moveData: MoveData) => Promise<void>;
   
+  // Battleship setup - commits to the fleet; the placement stays in this browser
+  placeFleet: (shipData: string) => Promise<void>;
+  
   // Polling
   startPolling: () => void;
   stopPolling: () => void;
@@ -87,6 +93,8 @@ export const useGameStore = create<GameState>((set, get) => ({
     try {
       get().stopPolling();
       await leaveRoom();
+      const room = get().room;
+      if (room) forgetBattleshipFleet(battleshipRoomKey(room));
       set({ room: null, isLoading: false });
     } catch (err) {
       set({ 
@@ -112,6 +120,23 @@ export const useGameStore = create<GameState>((set, get) => ({
     }
   },
   
+  placeFleet: async (shipData: string) => {
+    set({ isLoading: true, error: null });
+    try {
+      const room = get().room;
+      if (!room) throw new Error('Not in a room');
+      await placeBattleshipShips(battleshipRoomKey(room), shipData);
+      await get().syncRoom();
+      set({ isLoading: false });
+    } catch (err) {
+      set({ 
+        isLoading: false, 
+        error: err instanceof Error ? err.message : 'Failed to place fleet' 
+      });
+      throw err;
+    }
+  },
+  
   startPolling: () => {
     // Stop any existing polling
     get().stopPolling();